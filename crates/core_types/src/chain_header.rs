@@ -110,6 +110,19 @@ impl ChainHeader {
     pub fn provenances(&self) -> &Vec<Provenance> {
         &self.provenances
     }
+
+    /// Compares everything but the provenances, i.e. whether both headers make the
+    /// same statement about the chain regardless of who signed it and how.
+    /// A header that was merely re-signed is content_eq to the original
+    /// while PartialEq (and the address) tell them apart.
+    pub fn content_eq(&self, other: &ChainHeader) -> bool {
+        self.entry_type == other.entry_type
+            && self.entry_address == other.entry_address
+            && self.link == other.link
+            && self.link_same_type == other.link_same_type
+            && self.link_update_delete == other.link_update_delete
+            && self.timestamp == other.timestamp
+    }
 }
 
 impl AddressableContent for ChainHeader {
@@ -153,7 +166,9 @@ pub fn test_provenances(sig: &'static str) -> Vec<Provenance> {
 #[cfg(test)]
 pub mod tests {
     use crate::{
-        chain_header::{test_chain_header, test_provenances, ChainHeader},
+        chain_header::{
+            test_chain_header, test_chain_header_with_sig, test_provenances, ChainHeader,
+        },
         entry::{
             entry_type::{test_entry_type, test_entry_type_a, test_entry_type_b},
             test_entry, test_entry_a, test_entry_b,
@@ -241,6 +256,23 @@ pub mod tests {
         );
     }
 
+    #[test]
+    /// tests for ChainHeader::content_eq()
+    fn content_eq() {
+        // same content, different signature
+        let header = test_chain_header_with_sig("sig", None);
+        let resigned = test_chain_header_with_sig("other sig", None);
+        assert_ne!(header, resigned);
+        assert!(header.content_eq(&resigned));
+
+        // different content
+        assert!(!test_chain_header_a().content_eq(&test_chain_header_b()));
+        assert!(!header.content_eq(&test_chain_header_with_sig(
+            "sig",
+            Some(test_header_address())
+        )));
+    }
+
     #[test]
     /// tests for ChainHeader::new()
     fn new() {
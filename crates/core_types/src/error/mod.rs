@@ -119,14 +119,69 @@ pub enum HolochainError {
     EntryNotFoundLocally,
     EntryIsPrivate,
     List(Vec<HolochainError>),
+    AspectTooLarge(usize, usize),
+    MissingCrudLink(String),
+    NotFound { address: HashString },
 }
 
 pub type HcResult<T> = Result<T, HolochainError>;
 
+/// Coarse category of a HolochainError, to branch on without matching error messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ErrorKind {
+    /// Data could not be (de)serialized
+    Decode,
+    /// An `Update` or `Deletion` header without the CRUD link it needs
+    MissingCrudLink,
+    /// Data is inconsistent in itself or with what it refers to, e.g. failed validation
+    Inconsistent,
+    /// Data exceeds a size cap
+    TooLarge,
+    /// Something that was looked up is not there
+    NotFound,
+    Other,
+}
+
 impl HolochainError {
     pub fn new(msg: &str) -> HolochainError {
         HolochainError::ErrorGeneric(msg.to_string())
     }
+
+    /// The category of this error.
+    /// A List is of the kind all of its errors share, Other if they differ.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            SerializationError(_) => ErrorKind::Decode,
+            MissingCrudLink(_) => ErrorKind::MissingCrudLink,
+            ValidationFailed(_) | DnaHashMismatch(_, _) => ErrorKind::Inconsistent,
+            AspectTooLarge(_, _) => ErrorKind::TooLarge,
+            DnaMissing | EntryNotFoundLocally | NotFound { .. } => ErrorKind::NotFound,
+            List(errors) => {
+                let mut kinds = errors.iter().map(HolochainError::kind);
+                match kinds.next() {
+                    Some(first) if kinds.all(|kind| kind == first) => first,
+                    _ => ErrorKind::Other,
+                }
+            }
+            ErrorGeneric(_)
+            | CryptoError(_)
+            | CryptoServiceError(_)
+            | NotImplemented(_)
+            | LoggingError
+            | Dna(_)
+            | IoError(_)
+            | InvalidOperationOnSysEntry
+            | CapabilityCheckFailed
+            | ValidationPending
+            | Ribosome(_)
+            | RibosomeFailed(_)
+            | ConfigError(_)
+            | Timeout(_)
+            | InitializationFailed(_)
+            | LifecycleError(_)
+            | EntryIsPrivate => ErrorKind::Other,
+        }
+    }
 }
 
 impl From<rust_base58::base58::FromBase58Error> for HolochainError {
@@ -178,6 +233,13 @@ impl fmt::Display for HolochainError {
                     .join("\n");
                 write!(f, "A list of errors has been generated {}", error_list)
             }
+            AspectTooLarge(size, max) => write!(
+                f,
+                "Aspect serializes to {} bytes which exceeds the cap of {} bytes",
+                size, max
+            ),
+            MissingCrudLink(err_msg) => write!(f, "{}", err_msg),
+            NotFound { address } => write!(f, "{} could not be found", address),
         }
     }
 }
@@ -424,11 +486,56 @@ mod tests {
                 HolochainError::EntryIsPrivate,
                 "The requested entry is private and should not be shared via gossip",
             ),
+            (
+                HolochainError::AspectTooLarge(11, 10),
+                "Aspect serializes to 11 bytes which exceeds the cap of 10 bytes",
+            ),
+            (HolochainError::MissingCrudLink(String::from("foo")), "foo"),
+            (
+                HolochainError::NotFound {
+                    address: HashString::from("foo"),
+                },
+                "foo could not be found",
+            ),
         ] {
             assert_eq!(output, &input.to_string());
         }
     }
 
+    #[test]
+    fn error_kind_test() {
+        assert_eq!(
+            HolochainError::SerializationError(String::from("foo")).kind(),
+            ErrorKind::Decode
+        );
+        assert_eq!(
+            HolochainError::AspectTooLarge(11, 10).kind(),
+            ErrorKind::TooLarge
+        );
+        assert_eq!(
+            HolochainError::ErrorGeneric(String::from("foo")).kind(),
+            ErrorKind::Other
+        );
+        assert_eq!(
+            HolochainError::NotFound {
+                address: HashString::from("foo")
+            }
+            .kind(),
+            ErrorKind::NotFound
+        );
+        let failures = vec![
+            HolochainError::ValidationFailed(String::from("foo")),
+            HolochainError::ValidationFailed(String::from("bar")),
+        ];
+        assert_eq!(
+            HolochainError::List(failures.clone()).kind(),
+            ErrorKind::Inconsistent
+        );
+        let mut mixed = failures;
+        mixed.push(HolochainError::EntryNotFoundLocally);
+        assert_eq!(HolochainError::List(mixed).kind(), ErrorKind::Other);
+    }
+
     #[test]
     fn core_error_to_string() {
         let error =
@@ -210,6 +210,9 @@ impl From<HolochainError> for RibosomeErrorCode {
             HolochainError::EntryNotFoundLocally => RibosomeErrorCode::Unspecified,
             HolochainError::EntryIsPrivate => RibosomeErrorCode::Unspecified,
             HolochainError::List(_) => RibosomeErrorCode::Unspecified,
+            HolochainError::AspectTooLarge(_, _) => RibosomeErrorCode::Unspecified,
+            HolochainError::MissingCrudLink(_) => RibosomeErrorCode::Unspecified,
+            HolochainError::NotFound { .. } => RibosomeErrorCode::Unspecified,
         }
     }
 }
@@ -10,6 +10,8 @@
 extern crate backtrace;
 extern crate base64;
 extern crate chrono;
+#[cfg(feature = "compression")]
+extern crate flate2;
 extern crate futures;
 #[macro_use]
 extern crate lazy_static;
@@ -62,6 +62,11 @@ impl LinkData {
         &self.link
     }
 
+    /// The header that was on top of the author's chain when this link was committed
+    pub fn top_chain_header(&self) -> &ChainHeader {
+        &self.top_chain_header
+    }
+
     pub fn from_link(
         link: &Link,
         action_kind: LinkActionKind,
@@ -101,9 +106,12 @@ pub fn example_link_add() -> LinkData {
 pub mod tests {
 
     use crate::{
+        agent::test_agent_id,
+        chain_header::{test_chain_header, test_chain_header_with_sig},
         entry::{test_entry_a, test_entry_b, Entry},
         link::{
-            example_link, example_link_action_kind, example_link_type, link_data::example_link_add,
+            example_link, example_link_action_kind, example_link_type,
+            link_data::{example_link_add, LinkData},
         },
     };
     use holochain_json_api::json::JsonString;
@@ -160,6 +168,14 @@ pub mod tests {
         assert_eq!(&example_link(), example_link_add().link(),);
     }
 
+    #[test]
+    fn link_top_chain_header_test() {
+        assert_eq!(&test_chain_header(), example_link_add().top_chain_header());
+        let header = test_chain_header_with_sig("other sig", None);
+        let removal = LinkData::remove_from_link(&example_link(), header.clone(), test_agent_id());
+        assert_eq!(&header, removal.top_chain_header());
+    }
+
     #[test]
     /// show ToString for LinkAdd
     fn link_entry_to_string_test() {
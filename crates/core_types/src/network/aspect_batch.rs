@@ -0,0 +1,759 @@
+//! Functions that look at a whole batch of EntryAspects at once, e.g. to
+//! extract the link graph that the batch describes.
+
+use crate::{
+    chain_header::ChainHeader, entry::Entry, error::HolochainError, link::Link,
+    network::entry_aspect::EntryAspect,
+};
+use holochain_persistence_api::cas::content::{Address, AddressableContent};
+use serde_json;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+};
+
+/// All links of the `LinkAdd` and `LinkRemove` aspects in the batch, in order,
+/// each flagged with `true` if it comes from a removal.
+/// Non-link aspects are skipped.
+pub fn collect_links(aspects: &[EntryAspect]) -> Vec<(Link, bool)> {
+    aspects
+        .iter()
+        .filter_map(|aspect| match aspect {
+            EntryAspect::LinkAdd(link_data, _) => Some((link_data.link.clone(), false)),
+            EntryAspect::LinkRemove((link_data, _), _) => Some((link_data.link.clone(), true)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The links added by the `LinkAdd` aspects of the batch as an edge list of
+/// `(base, target, label)` with label `"<link type>:<tag>"`, e.g. for graph exports.
+/// Removals are not included, see collect_links() for those.
+pub fn link_edges(aspects: &[EntryAspect]) -> Vec<(Address, Address, String)> {
+    aspects
+        .iter()
+        .filter_map(|aspect| match aspect {
+            EntryAspect::LinkAdd(link_data, _) => {
+                let link = link_data.link();
+                Some((
+                    link.base().clone(),
+                    link.target().clone(),
+                    format!("{}:{}", link.link_type(), link.tag()),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The addresses of all entries that the `Deletion` aspects in the batch delete,
+/// i.e. their entry_address(). Other aspects are ignored.
+/// Errors if a deletion header is missing its CRUD link.
+pub fn tombstone_index(aspects: &[EntryAspect]) -> Result<HashSet<Address>, HolochainError> {
+    let mut tombstones = HashSet::new();
+    for aspect in aspects {
+        if let EntryAspect::Deletion(_) = aspect {
+            tombstones.insert(aspect.entry_address()?);
+        }
+    }
+    Ok(tombstones)
+}
+
+/// The bases that meta aspects of the batch refer to but that no `Content` or `Header`
+/// aspect in the batch is for, i.e. the entry_address() of `LinkAdd`, `LinkRemove`,
+/// `Update` and `Deletion` aspects. Orphans hint at gossip that did not arrive
+/// (yet). Each address is reported once, in order of first reference.
+/// Errors if an update or deletion header is missing its CRUD link.
+pub fn find_orphans(aspects: &[EntryAspect]) -> Result<Vec<Address>, HolochainError> {
+    let present: HashSet<&Address> = aspects
+        .iter()
+        .filter_map(|aspect| match aspect {
+            EntryAspect::Content(_, header) | EntryAspect::Header(header) => {
+                Some(header.entry_address())
+            }
+            _ => None,
+        })
+        .collect();
+    let mut reported = HashSet::new();
+    let mut orphans = Vec::new();
+    for aspect in aspects {
+        match aspect {
+            EntryAspect::Content(_, _) | EntryAspect::Header(_) => (),
+            _ => {
+                let base = aspect.entry_address()?;
+                if !present.contains(&base) && reported.insert(base.clone()) {
+                    orphans.push(base);
+                }
+            }
+        }
+    }
+    Ok(orphans)
+}
+
+/// Decodes a JSON array of aspects, keeping the ones that decode and pass
+/// EntryAspect::validate_self_consistency() (self links allowed) and reporting
+/// every other element with its index and the reason.
+/// Only errors as a whole if `json` is not a JSON array.
+pub fn decode_and_partition(
+    json: &str,
+) -> Result<(Vec<EntryAspect>, Vec<(usize, HolochainError)>), HolochainError> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(json)?;
+    let mut aspects = Vec::with_capacity(values.len());
+    let mut failures = Vec::new();
+    for (index, value) in values.into_iter().enumerate() {
+        let checked = EntryAspect::try_from(value).and_then(|aspect| {
+            aspect.validate_self_consistency(false)?;
+            Ok(aspect)
+        });
+        match checked {
+            Ok(aspect) => aspects.push(aspect),
+            Err(error) => failures.push((index, error)),
+        }
+    }
+    Ok((aspects, failures))
+}
+
+/// The edit history described by a set of `Update` aspects: which new entry
+/// version replaced which base, as given by the update headers' CRUD links.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionTree {
+    bases: HashMap<Address, Address>,
+}
+
+impl VersionTree {
+    /// The address the entry at `new_address` replaced, if it is an update.
+    pub fn base_of(&self, new_address: &Address) -> Option<&Address> {
+        self.bases.get(new_address)
+    }
+
+    /// Number of updates in the tree
+    pub fn len(&self) -> usize {
+        self.bases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bases.is_empty()
+    }
+
+    /// The new addresses of all updates that did not get superseded by another update.
+    pub fn heads(&self) -> HashSet<Address> {
+        let replaced: HashSet<&Address> = self.bases.values().collect();
+        self.bases
+            .keys()
+            .filter(|new_address| !replaced.contains(new_address))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Default for the `max_depth` of build_version_tree()
+pub const DEFAULT_MAX_VERSION_DEPTH: usize = 1024;
+
+/// Builds the VersionTree of all `Update` aspects in the batch, other aspects are ignored.
+/// Errors if an update header is missing its CRUD link, or if a chain of updates is
+/// more than `max_depth` updates long (which includes update cycles, as they can come
+/// with untrusted data).
+pub fn build_version_tree(
+    aspects: &[EntryAspect],
+    max_depth: usize,
+) -> Result<VersionTree, HolochainError> {
+    let mut tree = VersionTree::default();
+    for aspect in aspects {
+        if let EntryAspect::Update(_, header) = aspect {
+            tree.bases
+                .insert(header.entry_address().clone(), aspect.entry_address()?);
+        }
+    }
+    check_depth(&tree.bases, max_depth)?;
+    Ok(tree)
+}
+
+/// The addresses of the current versions of all app entries in the batch: of every
+/// chain of `Update`s the latest version, and every app entry of a `Content` aspect
+/// that did not get updated. Versions whose chain has a deleted version (see
+/// tombstone_index()) are left out, as deleting any version deletes the entry.
+/// Errors like build_version_tree() with DEFAULT_MAX_VERSION_DEPTH and tombstone_index().
+pub fn live_heads(aspects: &[EntryAspect]) -> Result<HashSet<Address>, HolochainError> {
+    let tree = build_version_tree(aspects, DEFAULT_MAX_VERSION_DEPTH)?;
+    let tombstones = tombstone_index(aspects)?;
+    let replaced: HashSet<&Address> = tree.bases.values().collect();
+
+    let mut candidates = tree.heads();
+    for aspect in aspects {
+        if let EntryAspect::Content(_, header) = aspect {
+            if header.entry_type().is_app() && !replaced.contains(header.entry_address()) {
+                candidates.insert(header.entry_address().clone());
+            }
+        }
+    }
+
+    Ok(candidates
+        .into_iter()
+        .filter(|head| !has_deleted_version(head, &tree, &tombstones))
+        .collect())
+}
+
+/// The aspects of the batch that make up its current state, like a materialized view:
+/// * for every live_heads() version the first `Content` or `Update` aspect of it
+/// * every `LinkAdd` whose entry no `LinkRemove` in the batch removes, i.e. whose
+///   header's entry address is not among the addresses a removal carries. So a link
+///   that got removed and added again is current, and a removal only removes the
+///   adds it names, not other adds of the same link.
+///
+/// Everything else is dropped: replaced and deleted versions, removals, deletions,
+/// headers and the `Content` aspects of system entries. Survivors keep their order.
+/// Errors like live_heads().
+pub fn resolve_current(aspects: Vec<EntryAspect>) -> Result<Vec<EntryAspect>, HolochainError> {
+    let mut heads = live_heads(&aspects)?;
+    let mut removed_adds: HashSet<Address> = HashSet::new();
+    for aspect in &aspects {
+        if let EntryAspect::LinkRemove((_, removed), _) = aspect {
+            removed_adds.extend(removed.iter().cloned());
+        }
+    }
+    Ok(aspects
+        .into_iter()
+        .filter(|aspect| match aspect {
+            EntryAspect::Content(_, header) | EntryAspect::Update(_, header) => {
+                heads.remove(header.entry_address())
+            }
+            EntryAspect::LinkAdd(_, header) => !removed_adds.contains(header.entry_address()),
+            _ => false,
+        })
+        .collect())
+}
+
+/// Whether `head` or any version it replaced is in `tombstones`.
+/// The depth of every chain in `tree` was checked by build_version_tree().
+fn has_deleted_version(head: &Address, tree: &VersionTree, tombstones: &HashSet<Address>) -> bool {
+    let mut version = Some(head);
+    while let Some(address) = version {
+        if tombstones.contains(address) {
+            return true;
+        }
+        version = tree.base_of(address);
+    }
+    false
+}
+
+/// Walks every update chain back to its root iteratively, remembering the depth of
+/// every visited address so that each address is walked over only once.
+fn check_depth(bases: &HashMap<Address, Address>, max_depth: usize) -> Result<(), HolochainError> {
+    fn too_deep(start: &Address, max_depth: usize) -> Result<(), HolochainError> {
+        Err(HolochainError::ErrorGeneric(format!(
+            "Update chain of {} is longer than the maximum depth of {}",
+            start, max_depth
+        )))
+    }
+    let mut depths: HashMap<&Address, usize> = HashMap::new();
+    for start in bases.keys() {
+        let mut path = Vec::new();
+        let mut current = start;
+        let root_depth = loop {
+            if let Some(depth) = depths.get(current) {
+                break *depth;
+            }
+            match bases.get(current) {
+                Some(base) => {
+                    path.push(current);
+                    if path.len() > max_depth {
+                        return too_deep(start, max_depth);
+                    }
+                    current = base;
+                }
+                None => break 0,
+            }
+        };
+        if root_depth + path.len() > max_depth {
+            return too_deep(start, max_depth);
+        }
+        for (index, address) in path.into_iter().rev().enumerate() {
+            depths.insert(address, root_depth + index + 1);
+        }
+    }
+    Ok(())
+}
+
+/// The meta aspect an entry is the source of, if it is a CRUD or link entry.
+/// Mirrors entry_to_meta_aspect() in core's network handler.
+fn meta_aspect(entry: Entry, header: ChainHeader) -> Option<EntryAspect> {
+    match entry {
+        Entry::App(app_type, app_value) => header
+            .link_update_delete()
+            .map(|_| EntryAspect::Update(Entry::App(app_type, app_value), header)),
+        Entry::LinkAdd(link_data) => Some(EntryAspect::LinkAdd(link_data, header)),
+        Entry::LinkRemove(removal) => Some(EntryAspect::LinkRemove(removal, header)),
+        Entry::Deletion(_) => Some(EntryAspect::Deletion(header)),
+        _ => None,
+    }
+}
+
+/// All aspects of a source chain segment, in chain order: the `Content` aspect of
+/// each entry, directly followed by the meta aspect it is the source of (if any).
+/// Errors if a header is not the header of the entry it is paired with.
+pub fn chain_to_aspects(
+    segment: Vec<(Entry, ChainHeader)>,
+) -> Result<Vec<EntryAspect>, HolochainError> {
+    let mut aspects = Vec::with_capacity(segment.len());
+    for (entry, header) in segment {
+        if entry.address() != *header.entry_address() {
+            return Err(HolochainError::ErrorGeneric(format!(
+                "Header for entry {} is paired with entry {}",
+                header.entry_address(),
+                entry.address()
+            )));
+        }
+        aspects.push(EntryAspect::Content(entry.clone(), header.clone()));
+        if let Some(meta) = meta_aspect(entry, header) {
+            aspects.push(meta);
+        }
+    }
+    Ok(aspects)
+}
+
+/// Lazily normalizes every aspect of `aspects`, see EntryAspect::normalize().
+pub fn into_canonical<I: IntoIterator<Item = EntryAspect>>(
+    aspects: I,
+) -> impl Iterator<Item = Result<EntryAspect, HolochainError>> {
+    aspects.into_iter().map(EntryAspect::normalize)
+}
+
+/// Moves the aspects with the highest gossip_priority() out of `aspects` into a batch
+/// whose total serialized_size() is at most `budget_bytes`. Aspects are taken greedily,
+/// skipping those that don't fit anymore, in the order of the returned batch:
+/// by descending priority, equal priorities by ascending aspect address. So the batch
+/// only depends on the set of aspects, not on their order in `aspects`.
+/// The aspects left in `aspects` keep their order.
+pub fn pack_aspects(
+    aspects: &mut Vec<EntryAspect>,
+    budget_bytes: usize,
+) -> Result<Vec<EntryAspect>, HolochainError> {
+    let mut sizes = Vec::with_capacity(aspects.len());
+    for aspect in aspects.iter() {
+        sizes.push(aspect.serialized_size()?);
+    }
+    let keys: Vec<(Reverse<u8>, Address)> = aspects
+        .iter()
+        .map(|aspect| (Reverse(aspect.gossip_priority()), aspect.address()))
+        .collect();
+    let mut by_priority: Vec<usize> = (0..aspects.len()).collect();
+    by_priority.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+
+    let mut remaining = budget_bytes;
+    let mut packed = vec![false; aspects.len()];
+    let mut order = Vec::new();
+    for index in by_priority {
+        if sizes[index] <= remaining {
+            remaining -= sizes[index];
+            packed[index] = true;
+            order.push(index);
+        }
+    }
+
+    let mut taken: Vec<Option<EntryAspect>> = Vec::with_capacity(aspects.len());
+    let mut kept = Vec::with_capacity(aspects.len() - order.len());
+    for (index, aspect) in aspects.drain(..).enumerate() {
+        if packed[index] {
+            taken.push(Some(aspect));
+        } else {
+            taken.push(None);
+            kept.push(aspect);
+        }
+    }
+    *aspects = kept;
+    Ok(order
+        .into_iter()
+        .filter_map(|index| taken[index].take())
+        .collect())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::{
+            test_chain_header, test_chain_header_with_sig, test_provenances, ChainHeader,
+        },
+        entry::{entry_type::test_app_entry_type, test_entry, test_entry_b, Entry},
+        link::{
+            example_link,
+            link_data::{example_link_add, LinkData},
+        },
+        network::entry_aspect::tests::{
+            test_deletion_aspect, test_header_for, test_link_add_aspect,
+        },
+        time::test_iso_8601,
+    };
+    use holochain_json_api::json::RawString;
+
+    #[test]
+    fn collect_links_test() {
+        let link = example_link();
+        let add = LinkData::add_from_link(&link, test_chain_header(), test_agent_id());
+        let remove = LinkData::remove_from_link(&link, test_chain_header(), test_agent_id());
+        let aspects = vec![
+            EntryAspect::LinkAdd(add, test_chain_header()),
+            EntryAspect::Content(test_entry(), test_chain_header()),
+            EntryAspect::LinkRemove((remove, vec![test_entry().address()]), test_chain_header()),
+        ];
+
+        assert_eq!(
+            collect_links(&aspects),
+            vec![(link.clone(), false), (link, true)]
+        );
+    }
+
+    #[test]
+    fn link_edges_test() {
+        let (a, b) = (test_entry().address(), test_entry_b().address());
+        let add = |base: &Address, target: &Address, tag: &str| {
+            let link = Link::new(base, target, "foo-link-type", tag);
+            EntryAspect::LinkAdd(
+                LinkData::add_from_link(&link, test_chain_header(), test_agent_id()),
+                test_chain_header(),
+            )
+        };
+        let aspects = vec![
+            add(&a, &b, "tag-1"),
+            EntryAspect::Content(test_entry(), test_chain_header()),
+            add(&b, &a, "tag-2"),
+        ];
+
+        assert_eq!(
+            link_edges(&aspects),
+            vec![
+                (a.clone(), b.clone(), String::from("foo-link-type:tag-1")),
+                (b, a, String::from("foo-link-type:tag-2")),
+            ]
+        );
+    }
+
+    /// An update aspect replacing the entry `base` with a new entry of value `value`
+    pub fn test_update_of(base: &Address, value: &str) -> EntryAspect {
+        let entry = Entry::App(
+            test_app_entry_type(),
+            RawString::from(value.to_string()).into(),
+        );
+        let header = ChainHeader::new(
+            &entry.entry_type(),
+            &entry.address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &Some(base.clone()),
+            &test_iso_8601(),
+        );
+        EntryAspect::Update(entry, header)
+    }
+
+    #[test]
+    fn tombstone_index_test() {
+        let deletion_of = |deleted: Address| {
+            EntryAspect::Deletion(test_chain_header_with_sig("sig", Some(deleted)))
+        };
+        let index = tombstone_index(&[
+            deletion_of(test_entry().address()),
+            EntryAspect::Content(test_entry(), test_chain_header()),
+            deletion_of(test_entry_b().address()),
+        ])
+        .unwrap();
+        let expected: HashSet<Address> = vec![test_entry().address(), test_entry_b().address()]
+            .into_iter()
+            .collect();
+        assert_eq!(index, expected);
+
+        assert!(tombstone_index(&[EntryAspect::Deletion(test_chain_header())]).is_err());
+    }
+
+    #[test]
+    fn decode_and_partition_test() {
+        let good = EntryAspect::Content(test_entry(), test_chain_header());
+        let json = format!(
+            "[{}, {{\"Content\": \"not an aspect\"}}]",
+            String::from(good.content())
+        );
+        let (aspects, failures) = decode_and_partition(&json).unwrap();
+        assert_eq!(aspects, vec![good]);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 1);
+
+        // decodes, but the header is not the entry's header
+        let inconsistent = EntryAspect::Content(test_entry_b(), test_chain_header());
+        let json = format!("[{}]", String::from(inconsistent.content()));
+        let (aspects, failures) = decode_and_partition(&json).unwrap();
+        assert!(aspects.is_empty());
+        assert_eq!(failures[0].0, 0);
+
+        assert!(decode_and_partition("{}").is_err());
+    }
+
+    #[test]
+    fn build_version_tree_test() {
+        let a = test_entry().address();
+        let b = test_update_of(&a, "b");
+        let c = test_update_of(b.header().entry_address(), "c");
+        let b_address = b.header().entry_address().clone();
+        let c_address = c.header().entry_address().clone();
+
+        let tree = build_version_tree(
+            &[
+                c,
+                EntryAspect::Content(test_entry(), test_chain_header()),
+                b,
+            ],
+            DEFAULT_MAX_VERSION_DEPTH,
+        )
+        .unwrap();
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.base_of(&b_address), Some(&a));
+        assert_eq!(tree.base_of(&c_address), Some(&b_address));
+        let heads: HashSet<Address> = vec![c_address].into_iter().collect();
+        assert_eq!(tree.heads(), heads);
+    }
+
+    #[test]
+    fn build_version_tree_max_depth_test() {
+        let mut chain = Vec::new();
+        let mut base = test_entry().address();
+        for version in 0..6 {
+            let update = test_update_of(&base, &version.to_string());
+            base = update.header().entry_address().clone();
+            chain.push(update);
+        }
+        // newest first, so that the depth check can't rely on insertion order
+        chain.reverse();
+
+        assert_eq!(build_version_tree(&chain, 6).map(|tree| tree.len()), Ok(6));
+        assert!(build_version_tree(&chain, 5).is_err());
+        assert!(build_version_tree(&chain[1..], 5).is_ok());
+    }
+
+    #[test]
+    fn chain_to_aspects_test() {
+        let link_entry = Entry::LinkAdd(example_link_add());
+        let link_header = test_header_for(&link_entry, &test_iso_8601());
+        let app_header = test_header_for(&test_entry(), &test_iso_8601());
+        let aspects = chain_to_aspects(vec![
+            (test_entry(), app_header.clone()),
+            (link_entry.clone(), link_header.clone()),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            aspects,
+            vec![
+                EntryAspect::Content(test_entry(), app_header),
+                EntryAspect::Content(link_entry, link_header.clone()),
+                EntryAspect::LinkAdd(example_link_add(), link_header.clone()),
+            ]
+        );
+
+        assert!(chain_to_aspects(vec![(test_entry(), link_header)]).is_err());
+    }
+
+    #[test]
+    fn into_canonical_test() {
+        let batch = vec![
+            EntryAspect::Content(test_entry(), test_chain_header()),
+            test_update_of(&test_entry().address(), "b"),
+            // not self-consistent: the header is not the header of the entry
+            EntryAspect::Content(
+                test_entry(),
+                test_update_of(&test_entry().address(), "c")
+                    .header()
+                    .clone(),
+            ),
+        ];
+        let by_adapter: Vec<Result<EntryAspect, HolochainError>> =
+            into_canonical(batch.clone()).collect();
+        let by_hand: Vec<Result<EntryAspect, HolochainError>> =
+            batch.into_iter().map(EntryAspect::normalize).collect();
+        assert_eq!(by_adapter, by_hand);
+        assert!(by_adapter[2].is_err());
+    }
+
+    #[test]
+    fn pack_aspects_test() {
+        let content = EntryAspect::Content(test_entry(), test_chain_header());
+        let deletion = test_deletion_aspect();
+        let link_add = test_link_add_aspect();
+        fn size(aspect: &EntryAspect) -> usize {
+            aspect.serialized_size().unwrap()
+        }
+
+        // room for the deletion and one of the others
+        let budget = size(&deletion) + size(&content);
+        let mut aspects = vec![content.clone(), link_add, deletion.clone()];
+        let batch = pack_aspects(&mut aspects, budget).unwrap();
+
+        let packed_size: usize = batch.iter().map(size).sum();
+        assert!(packed_size <= budget);
+        assert_eq!(batch[0], deletion);
+        assert!(batch.len() >= 2);
+        assert_eq!(batch.len() + aspects.len(), 3);
+        for aspect in &batch {
+            assert!(!aspects.contains(aspect));
+        }
+
+        let mut aspects = vec![content, deletion];
+        assert_eq!(pack_aspects(&mut aspects, 0), Ok(Vec::new()));
+        assert_eq!(aspects.len(), 2);
+    }
+
+    /// `LinkAdd` of example_link() committed on top of `top`
+    fn example_link_add_on(top: ChainHeader) -> EntryAspect {
+        let link_data = LinkData::add_from_link(&example_link(), top, test_agent_id());
+        let header = test_header_for(&Entry::LinkAdd(link_data.clone()), &test_iso_8601());
+        EntryAspect::LinkAdd(link_data, header)
+    }
+
+    /// `LinkRemove` of example_link() that removes the given adds
+    fn example_link_remove_of(adds: &[&EntryAspect]) -> EntryAspect {
+        let removal = (
+            LinkData::remove_from_link(&example_link(), test_chain_header(), test_agent_id()),
+            adds.iter()
+                .map(|add| add.header().entry_address().clone())
+                .collect(),
+        );
+        let header = test_header_for(&Entry::LinkRemove(removal.clone()), &test_iso_8601());
+        EntryAspect::LinkRemove(removal, header)
+    }
+
+    #[test]
+    fn resolve_current_test() {
+        let base = EntryAspect::Content(test_entry(), test_chain_header());
+        let update = test_update_of(&test_entry().address(), "b");
+        let deleted_base = EntryAspect::Content(
+            test_entry_b(),
+            test_header_for(&test_entry_b(), &test_iso_8601()),
+        );
+        let deletion = EntryAspect::Deletion(test_chain_header_with_sig(
+            "sig",
+            Some(test_entry_b().address()),
+        ));
+        let removed_link_add = example_link_add_on(test_chain_header());
+        let link_remove = example_link_remove_of(&[&removed_link_add]);
+        let link = example_link();
+        let other_link = Link::new(link.base(), link.target(), link.link_type(), "other-tag");
+        let other_link = LinkData::add_from_link(&other_link, test_chain_header(), test_agent_id());
+        let live_link_add = EntryAspect::LinkAdd(
+            other_link.clone(),
+            test_header_for(&Entry::LinkAdd(other_link), &test_iso_8601()),
+        );
+
+        let resolved = resolve_current(vec![
+            base,
+            removed_link_add,
+            update.clone(),
+            deleted_base,
+            live_link_add.clone(),
+            deletion,
+            link_remove,
+            update.clone(),
+        ])
+        .unwrap();
+        assert_eq!(resolved, vec![update, live_link_add]);
+    }
+
+    #[test]
+    fn resolve_current_link_adds_test() {
+        let first_add = example_link_add_on(test_chain_header());
+        let second_add = example_link_add_on(test_chain_header_with_sig("other sig", None));
+        assert!(first_add.same_target(&second_add));
+        assert_ne!(first_add.address(), second_add.address());
+        let removal = example_link_remove_of(&[&first_add]);
+
+        // removed and added again
+        let re_added =
+            resolve_current(vec![first_add.clone(), removal.clone(), second_add.clone()]).unwrap();
+        assert_eq!(re_added, vec![second_add.clone()]);
+
+        // the removal names only one of two adds of the same link
+        let one_removed =
+            resolve_current(vec![first_add.clone(), second_add.clone(), removal]).unwrap();
+        assert_eq!(one_removed, vec![second_add.clone()]);
+
+        let both_removed = example_link_remove_of(&[&first_add, &second_add]);
+        assert_eq!(
+            resolve_current(vec![first_add, second_add, both_removed]),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn find_orphans_test() {
+        let link_add = test_link_add_aspect();
+        let base = link_add.as_link().unwrap().base().clone();
+        // the link and the update have the same base
+        let update = test_update_of(&base, "b");
+        let mut batch = vec![
+            link_add.clone(),
+            EntryAspect::Content(
+                test_entry_b(),
+                test_header_for(&test_entry_b(), &test_iso_8601()),
+            ),
+            update,
+            link_add,
+        ];
+        assert_eq!(find_orphans(&batch), Ok(vec![base]));
+
+        batch.push(EntryAspect::Content(test_entry(), test_chain_header()));
+        assert_eq!(find_orphans(&batch), Ok(Vec::new()));
+        assert!(find_orphans(&[EntryAspect::Deletion(test_chain_header())]).is_err());
+    }
+
+    #[test]
+    fn pack_aspects_tie_break_test() {
+        let a = EntryAspect::Content(test_entry(), test_chain_header());
+        let b = EntryAspect::Content(
+            test_entry_b(),
+            test_header_for(&test_entry_b(), &test_iso_8601()),
+        );
+        assert_eq!(a.gossip_priority(), b.gossip_priority());
+        let mut expected = vec![a.clone(), b.clone()];
+        expected.sort_by_key(AddressableContent::address);
+
+        let budget = a.serialized_size().unwrap() + b.serialized_size().unwrap();
+        let forward = pack_aspects(&mut vec![a.clone(), b.clone()], budget).unwrap();
+        let backward = pack_aspects(&mut vec![b, a], budget).unwrap();
+        assert_eq!(forward, expected);
+        assert_eq!(backward, expected);
+    }
+
+    #[test]
+    fn live_heads_test() {
+        let update = test_update_of(&test_entry().address(), "b");
+        let deleted_base = EntryAspect::Content(
+            test_entry_b(),
+            test_header_for(&test_entry_b(), &test_iso_8601()),
+        );
+        let deletion = EntryAspect::Deletion(test_chain_header_with_sig(
+            "sig",
+            Some(test_entry_b().address()),
+        ));
+        let batch = vec![
+            EntryAspect::Content(test_entry(), test_chain_header()),
+            update.clone(),
+            deleted_base,
+            deletion.clone(),
+        ];
+
+        let heads: HashSet<Address> = vec![update.header().entry_address().clone()]
+            .into_iter()
+            .collect();
+        assert_eq!(live_heads(&batch), Ok(heads));
+
+        // deleting the base deletes its updates as well
+        let deletion_of_base = EntryAspect::Deletion(test_chain_header_with_sig(
+            "sig",
+            Some(test_entry().address()),
+        ));
+        assert_eq!(
+            live_heads(&[update, deletion_of_base, deletion]),
+            Ok(HashSet::new())
+        );
+    }
+}
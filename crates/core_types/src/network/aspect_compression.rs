@@ -0,0 +1,69 @@
+//! Compressed form of EntryAspects for storage and transport (feature `compression`).
+//! The canonical JSON bytes (EntryAspect::canonical_bytes()) are zlib-compressed,
+//! which pays off most for large `Content` aspects.
+//!
+//! Compressed bytes are NOT for addressing: depending on the compression level
+//! and implementation the same aspect can compress to different bytes. The address
+//! is always computed from the uncompressed canonical bytes.
+
+use crate::{
+    error::HolochainError,
+    network::entry_aspect::{EntryAspect, MAX_ASPECT_BYTES},
+};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use std::{
+    convert::TryFrom,
+    io::{Read, Write},
+    str,
+};
+
+impl EntryAspect {
+    pub fn to_compressed(&self) -> Result<Vec<u8>, HolochainError> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.canonical_bytes()?)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Decompresses and strictly decodes an aspect written by to_compressed().
+    /// Refuses data that decompresses to more than MAX_ASPECT_BYTES, so that
+    /// small compressed inputs can't exhaust memory.
+    pub fn from_compressed(compressed: &[u8]) -> Result<EntryAspect, HolochainError> {
+        let mut json = Vec::new();
+        ZlibDecoder::new(compressed)
+            .take(MAX_ASPECT_BYTES as u64 + 1)
+            .read_to_end(&mut json)?;
+        if json.len() > MAX_ASPECT_BYTES {
+            return Err(HolochainError::AspectTooLarge(json.len(), MAX_ASPECT_BYTES));
+        }
+        EntryAspect::try_from(str::from_utf8(&json)?)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        entry::{entry_type::test_app_entry_type, Entry},
+        network::entry_aspect::tests::test_header_for,
+        time::test_iso_8601,
+    };
+    use holochain_json_api::json::RawString;
+    use holochain_persistence_api::cas::content::AddressableContent;
+
+    #[test]
+    fn compressed_round_trip_test() {
+        let payload = "all work and no play makes jack a dull boy ".repeat(10_000);
+        let entry = Entry::App(test_app_entry_type(), RawString::from(payload).into());
+        let aspect = EntryAspect::Content(entry.clone(), test_header_for(&entry, &test_iso_8601()));
+
+        let compressed = aspect.to_compressed().unwrap();
+        assert!(compressed.len() < aspect.canonical_bytes().unwrap().len() / 10);
+
+        let decompressed = EntryAspect::from_compressed(&compressed).unwrap();
+        assert_eq!(decompressed, aspect);
+        assert_eq!(decompressed.address(), aspect.address());
+
+        assert!(EntryAspect::from_compressed(&compressed[..compressed.len() / 2]).is_err());
+        assert!(EntryAspect::from_compressed(b"not compressed").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! AspectCounter counts aspects by AspectType, e.g. for telemetry on gossip.
+//! Counters kept per shard or thread add up with `+`, merge() or sum().
+
+use crate::network::entry_aspect::{AspectType, EntryAspect};
+use std::{
+    collections::BTreeMap,
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AspectCounter {
+    counts: BTreeMap<AspectType, usize>,
+}
+
+impl AspectCounter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Counts `aspect` under its aspect type.
+    pub fn record(&mut self, aspect: &EntryAspect) {
+        *self.counts.entry(aspect.aspect_type()).or_insert(0) += 1;
+    }
+
+    /// Number of recorded aspects of `aspect_type`
+    pub fn count(&self, aspect_type: AspectType) -> usize {
+        self.counts.get(&aspect_type).cloned().unwrap_or(0)
+    }
+
+    /// Number of recorded aspects of all types
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Adds the counts of `other` to this counter, type by type.
+    pub fn merge(&mut self, other: &AspectCounter) {
+        for (aspect_type, count) in &other.counts {
+            *self.counts.entry(*aspect_type).or_insert(0) += count;
+        }
+    }
+}
+
+impl Add for AspectCounter {
+    type Output = AspectCounter;
+
+    fn add(mut self, other: AspectCounter) -> AspectCounter {
+        self.merge(&other);
+        self
+    }
+}
+
+impl AddAssign for AspectCounter {
+    fn add_assign(&mut self, other: AspectCounter) {
+        self.merge(&other)
+    }
+}
+
+impl Sum for AspectCounter {
+    fn sum<I: Iterator<Item = AspectCounter>>(counters: I) -> AspectCounter {
+        counters.fold(AspectCounter::new(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a AspectCounter> for AspectCounter {
+    fn sum<I: Iterator<Item = &'a AspectCounter>>(counters: I) -> AspectCounter {
+        let mut total = AspectCounter::new();
+        for counter in counters {
+            total.merge(counter);
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::network::entry_aspect::tests::{
+        test_content_aspect, test_deletion_aspect, test_link_add_aspect,
+    };
+
+    #[test]
+    fn merge_test() {
+        let mut first = AspectCounter::new();
+        first.record(&test_content_aspect());
+        first.record(&test_link_add_aspect());
+        let mut second = AspectCounter::new();
+        second.record(&test_content_aspect());
+        second.record(&test_deletion_aspect());
+
+        let sum = first.clone() + second.clone();
+        assert_eq!(sum.count(AspectType::Content), 2);
+        assert_eq!(sum.count(AspectType::LinkAdd), 1);
+        assert_eq!(sum.count(AspectType::Deletion), 1);
+        assert_eq!(sum.count(AspectType::Update), 0);
+        assert_eq!(sum.total(), 4);
+
+        let mut merged = first.clone();
+        merged.merge(&second);
+        assert_eq!(merged, sum);
+        let mut added = first.clone();
+        added += second.clone();
+        assert_eq!(added, sum);
+
+        let counters = vec![first, second];
+        assert_eq!(counters.iter().sum::<AspectCounter>(), sum);
+        assert_eq!(counters.into_iter().sum::<AspectCounter>(), sum);
+    }
+}
@@ -0,0 +1,158 @@
+//! AspectFilter is a reusable predicate over EntryAspects that gets built
+//! from combinators, e.g. "content aspects for entry X authored by Y":
+//!
+//! ```
+//! use holochain_core_types::{
+//!     chain_header::test_chain_header,
+//!     entry::test_entry,
+//!     network::{
+//!         aspect_filter::AspectFilter,
+//!         entry_aspect::{AspectType, EntryAspect},
+//!     },
+//! };
+//!
+//! let header = test_chain_header();
+//! let filter = AspectFilter::new()
+//!     .of_type(AspectType::Content)
+//!     .for_entry(header.entry_address().clone());
+//!
+//! assert!(filter.matches(&EntryAspect::Content(test_entry(), header.clone())));
+//! assert!(!filter.matches(&EntryAspect::Header(header)));
+//! ```
+
+use crate::network::entry_aspect::{AspectType, EntryAspect};
+use holochain_persistence_api::cas::content::Address;
+
+/// Matches the aspects that satisfy all of the configured predicates.
+/// An AspectFilter without any predicates matches every aspect.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AspectFilter {
+    aspect_type: Option<AspectType>,
+    entry_address: Option<Address>,
+    author: Option<Address>,
+    link_tag: Option<String>,
+}
+
+impl AspectFilter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only aspects of the given type
+    pub fn of_type(mut self, aspect_type: AspectType) -> Self {
+        self.aspect_type = Some(aspect_type);
+        self
+    }
+
+    /// Only aspects whose entry_address() is `address`
+    pub fn for_entry(mut self, address: Address) -> Self {
+        self.entry_address = Some(address);
+        self
+    }
+
+    /// Only aspects whose header has a provenance from `author`
+    pub fn by_author(mut self, author: Address) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Only link aspects (`LinkAdd` and `LinkRemove`) of links with this tag
+    pub fn link_tag(mut self, tag: String) -> Self {
+        self.link_tag = Some(tag);
+        self
+    }
+
+    pub fn matches(&self, aspect: &EntryAspect) -> bool {
+        if let Some(aspect_type) = self.aspect_type {
+            if aspect.aspect_type() != aspect_type {
+                return false;
+            }
+        }
+        if let Some(address) = &self.entry_address {
+            match aspect.entry_address() {
+                Ok(ref entry_address) if entry_address == address => (),
+                _ => return false,
+            }
+        }
+        if let Some(author) = &self.author {
+            if !aspect
+                .header()
+                .provenances()
+                .iter()
+                .any(|provenance| provenance.0 == *author)
+            {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.link_tag {
+            if aspect.link_tag() != Some(tag.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::test_chain_header,
+        entry::{test_entry, test_entry_b},
+        network::entry_aspect::tests::{
+            test_content_aspect, test_deletion_aspect, test_header_for, test_link_add_aspect,
+            test_update_aspect,
+        },
+        time::test_iso_8601,
+    };
+    use holochain_persistence_api::cas::content::AddressableContent;
+
+    fn test_batch() -> Vec<EntryAspect> {
+        vec![
+            test_content_aspect(),
+            EntryAspect::Content(
+                test_entry_b(),
+                test_header_for(&test_entry_b(), &test_iso_8601()),
+            ),
+            EntryAspect::Header(test_chain_header()),
+            test_link_add_aspect(),
+            test_update_aspect(),
+            test_deletion_aspect(),
+        ]
+    }
+
+    #[test]
+    fn empty_filter_matches_all_test() {
+        assert!(test_batch()
+            .iter()
+            .all(|aspect| AspectFilter::new().matches(aspect)));
+    }
+
+    #[test]
+    fn type_and_entry_filter_test() {
+        let filter = AspectFilter::new()
+            .of_type(AspectType::Content)
+            .for_entry(test_entry().address());
+        let matching: Vec<EntryAspect> = test_batch()
+            .into_iter()
+            .filter(|aspect| filter.matches(aspect))
+            .collect();
+        assert_eq!(matching, vec![test_content_aspect()]);
+    }
+
+    #[test]
+    fn author_and_link_tag_filter_test() {
+        let by_author = AspectFilter::new().by_author(test_agent_id().address());
+        assert!(by_author.matches(&test_content_aspect()));
+        let by_other = AspectFilter::new().by_author(test_entry().address());
+        assert!(!by_other.matches(&test_content_aspect()));
+
+        let tagged = AspectFilter::new().link_tag(String::from("foo-link-tag"));
+        assert!(tagged.matches(&test_link_add_aspect()));
+        assert!(!tagged.matches(&test_content_aspect()));
+        assert!(!AspectFilter::new()
+            .link_tag(String::from("other-tag"))
+            .matches(&test_link_add_aspect()));
+    }
+}
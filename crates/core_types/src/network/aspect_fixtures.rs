@@ -0,0 +1,186 @@
+//! Hand-built EntryAspects, one per variant, together with their canonical
+//! JSON and content address pinned as constants.
+//! Everything that addresses or (de)serializes aspects can check against these
+//! that aspects still decode and address exactly as before. The fixtures are
+//! built field by field instead of from the other test helpers, so that changes
+//! to those don't move the pinned values.
+//!
+//! Available in this crate's tests and, for downstream crates, with the
+//! `test_support` feature.
+
+use crate::{
+    agent::AgentId,
+    chain_header::ChainHeader,
+    entry::{
+        deletion_entry::DeletionEntry,
+        entry_type::{AppEntryType, EntryType},
+        Entry,
+    },
+    link::{link_data::LinkData, Link, LinkActionKind},
+    network::entry_aspect::EntryAspect,
+    signature::{Provenance, Signature},
+    time::Iso8601,
+};
+use holochain_json_api::json::JsonString;
+use holochain_persistence_api::cas::content::{Address, AddressableContent};
+
+// Pinned canonical JSON (i.e. content()) and address of each fixture.
+// These must only ever change together with a deliberate change of the aspect format.
+pub const CONTENT_JSON: &str = r#"{"Content":[{"App":["fixtureEntryType","\"fixture entry\""]},{"entry_type":{"App":"fixtureEntryType"},"entry_address":"QmWS6Df67vsH1RZvH6q9necp7C5n5hudBrcWtq7D9bt3k4","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":null,"timestamp":"2018-10-11T03:23:38+00:00"}]}"#;
+pub const CONTENT_ADDRESS: &str = "QmNrsBLRmchg5ux7DsAqbBqtpbdfJTgMq7LmHhQzAnU5yG";
+
+pub const HEADER_JSON: &str = r#"{"Header":{"entry_type":{"App":"fixtureEntryType"},"entry_address":"QmWS6Df67vsH1RZvH6q9necp7C5n5hudBrcWtq7D9bt3k4","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":null,"timestamp":"2018-10-11T03:23:38+00:00"}}"#;
+pub const HEADER_ADDRESS: &str = "QmeDCTX7XiEzNCeFCFXkckiEM41QDoUJtuYHMPr1mii3bA";
+
+pub const LINK_ADD_JSON: &str = r#"{"LinkAdd":[{"action_kind":"ADD","link":{"base":"QmFixtureBase","target":"QmFixtureTarget","link_type":"fixture-link-type","tag":"fixture-tag"},"top_chain_header":{"entry_type":{"App":"fixtureEntryType"},"entry_address":"QmWS6Df67vsH1RZvH6q9necp7C5n5hudBrcWtq7D9bt3k4","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":null,"timestamp":"2018-10-11T03:23:38+00:00"},"agent_id":{"nick":"fixture","pub_sign_key":"HcScFixtureAgent"}},{"entry_type":"LinkAdd","entry_address":"Qmf5bbmfEa6wgVqCNZAoyqoyisHXcCV5xY9Jy4mX4n2VtB","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":null,"timestamp":"2018-10-11T03:23:38+00:00"}]}"#;
+pub const LINK_ADD_ADDRESS: &str = "QmYrXVbmvpJCdHQaqMockgWZNd6XopvVtWoHfMjzgCMv2p";
+
+pub const LINK_REMOVE_JSON: &str = r#"{"LinkRemove":[[{"action_kind":"REMOVE","link":{"base":"QmFixtureBase","target":"QmFixtureTarget","link_type":"fixture-link-type","tag":"fixture-tag"},"top_chain_header":{"entry_type":{"App":"fixtureEntryType"},"entry_address":"QmWS6Df67vsH1RZvH6q9necp7C5n5hudBrcWtq7D9bt3k4","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":null,"timestamp":"2018-10-11T03:23:38+00:00"},"agent_id":{"nick":"fixture","pub_sign_key":"HcScFixtureAgent"}},["Qmf5bbmfEa6wgVqCNZAoyqoyisHXcCV5xY9Jy4mX4n2VtB"]],{"entry_type":"LinkRemove","entry_address":"QmcF51jEzxBps27P9SST18F5aNWvMekxd2w7pDpmU35MVp","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":null,"timestamp":"2018-10-11T03:23:38+00:00"}]}"#;
+pub const LINK_REMOVE_ADDRESS: &str = "QmRXUthmzKDTy4DVXP1BZLqUqxTZV5i54cR2d4hR2wLAdL";
+
+pub const UPDATE_JSON: &str = r#"{"Update":[{"App":["fixtureEntryType","\"fixture entry v2\""]},{"entry_type":{"App":"fixtureEntryType"},"entry_address":"QmahB17CSoQrtNGTf8DCnaRx67TEBc3DBCjihv6cyBtZu5","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":"QmWS6Df67vsH1RZvH6q9necp7C5n5hudBrcWtq7D9bt3k4","timestamp":"2018-10-11T03:23:38+00:00"}]}"#;
+pub const UPDATE_ADDRESS: &str = "QmT6s7KExVC9npKqAhzivZgJZgfcD8SMFzYCsBMfwv3KzW";
+
+pub const DELETION_JSON: &str = r#"{"Deletion":{"entry_type":"Deletion","entry_address":"QmdHCNgZnfF5wbPsrm43xkLCWQ4aCz1rwsDz1pRWgS3kDG","provenances":[["HcScFixtureAuthor","fixture-signature"]],"link":null,"link_same_type":null,"link_update_delete":"QmWS6Df67vsH1RZvH6q9necp7C5n5hudBrcWtq7D9bt3k4","timestamp":"2018-10-11T03:23:38+00:00"}}"#;
+pub const DELETION_ADDRESS: &str = "QmUSyK4HLwtAKkq37nqdeUfXsVb4m1FL3ZPcbwUU7sfF3Z";
+
+fn fixture_header(
+    entry_type: EntryType,
+    entry_address: Address,
+    link_update_delete: Option<Address>,
+) -> ChainHeader {
+    ChainHeader::new(
+        &entry_type,
+        &entry_address,
+        &vec![Provenance(
+            Address::from("HcScFixtureAuthor"),
+            Signature::from("fixture-signature"),
+        )],
+        &None,
+        &None,
+        &link_update_delete,
+        // 2018-10-11T03:23:38+00:00
+        &Iso8601::from(1_539_228_218),
+    )
+}
+
+fn fixture_app_entry(value: &str) -> Entry {
+    Entry::App(
+        AppEntryType::from("fixtureEntryType"),
+        JsonString::from_json(value),
+    )
+}
+
+fn fixture_link_data(action_kind: LinkActionKind) -> LinkData {
+    let link = Link::new(
+        &Address::from("QmFixtureBase"),
+        &Address::from("QmFixtureTarget"),
+        "fixture-link-type",
+        "fixture-tag",
+    );
+    LinkData::from_link(
+        &link,
+        action_kind,
+        fixture_content_header(),
+        AgentId::new("fixture", String::from("HcScFixtureAgent")),
+    )
+}
+
+fn fixture_content_header() -> ChainHeader {
+    let entry = fixture_app_entry("\"fixture entry\"");
+    fixture_header(entry.entry_type(), entry.address(), None)
+}
+
+pub fn fixture_content_aspect() -> EntryAspect {
+    EntryAspect::Content(
+        fixture_app_entry("\"fixture entry\""),
+        fixture_content_header(),
+    )
+}
+
+pub fn fixture_header_aspect() -> EntryAspect {
+    EntryAspect::Header(fixture_content_header())
+}
+
+pub fn fixture_link_add_aspect() -> EntryAspect {
+    let link_data = fixture_link_data(LinkActionKind::ADD);
+    let entry = Entry::LinkAdd(link_data.clone());
+    EntryAspect::LinkAdd(
+        link_data,
+        fixture_header(EntryType::LinkAdd, entry.address(), None),
+    )
+}
+
+pub fn fixture_link_remove_aspect() -> EntryAspect {
+    let link_add_address = fixture_link_add_aspect().header().entry_address().clone();
+    let removal = (
+        fixture_link_data(LinkActionKind::REMOVE),
+        vec![link_add_address],
+    );
+    let entry = Entry::LinkRemove(removal.clone());
+    EntryAspect::LinkRemove(
+        removal,
+        fixture_header(EntryType::LinkRemove, entry.address(), None),
+    )
+}
+
+pub fn fixture_update_aspect() -> EntryAspect {
+    let base = fixture_content_header().entry_address().clone();
+    let entry = fixture_app_entry("\"fixture entry v2\"");
+    let header = fixture_header(entry.entry_type(), entry.address(), Some(base));
+    EntryAspect::Update(entry, header)
+}
+
+pub fn fixture_deletion_aspect() -> EntryAspect {
+    let deleted = fixture_content_header().entry_address().clone();
+    let entry = Entry::Deletion(DeletionEntry::new(deleted.clone()));
+    EntryAspect::Deletion(fixture_header(
+        EntryType::Deletion,
+        entry.address(),
+        Some(deleted),
+    ))
+}
+
+/// Every fixture with its pinned canonical JSON and address, in variant order
+pub fn fixtures() -> Vec<(EntryAspect, &'static str, &'static str)> {
+    vec![
+        (fixture_content_aspect(), CONTENT_JSON, CONTENT_ADDRESS),
+        (fixture_header_aspect(), HEADER_JSON, HEADER_ADDRESS),
+        (fixture_link_add_aspect(), LINK_ADD_JSON, LINK_ADD_ADDRESS),
+        (
+            fixture_link_remove_aspect(),
+            LINK_REMOVE_JSON,
+            LINK_REMOVE_ADDRESS,
+        ),
+        (fixture_update_aspect(), UPDATE_JSON, UPDATE_ADDRESS),
+        (fixture_deletion_aspect(), DELETION_JSON, DELETION_ADDRESS),
+    ]
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::network::entry_aspect::AspectType;
+
+    #[test]
+    fn fixtures_cover_all_variants_test() {
+        let types: Vec<AspectType> = fixtures()
+            .iter()
+            .map(|(aspect, _, _)| aspect.aspect_type())
+            .collect();
+        assert_eq!(types, AspectType::ALL.to_vec());
+    }
+
+    #[test]
+    fn fixtures_match_pinned_values_test() {
+        for (aspect, json, address) in fixtures() {
+            assert_eq!(String::from(aspect.content()), json);
+            assert_eq!(aspect.address(), Address::from(address));
+            assert_eq!(
+                EntryAspect::try_from_content(&JsonString::from_json(json)),
+                Ok(aspect.clone())
+            );
+            assert_eq!(aspect.validate_self_consistency(true), Ok(()));
+        }
+    }
+}
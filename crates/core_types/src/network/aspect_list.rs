@@ -0,0 +1,443 @@
+//! AspectList is an ordered collection of EntryAspects that can be
+//! deduplicated by aspect address, as needed when accumulating aspects
+//! from several sources (local chain, gossip, fetch responses).
+
+use crate::{
+    error::HolochainError,
+    network::entry_aspect::{AspectType, EntryAspect},
+};
+use holochain_persistence_api::cas::content::{Address, AddressableContent};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    iter::FromIterator,
+    slice, vec,
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AspectList {
+    aspects: Vec<EntryAspect>,
+}
+
+impl AspectList {
+    /// Create an empty list.
+    ///
+    /// # Examples
+    ///
+    /// Accumulate aspects and drop the ones that were seen twice:
+    ///
+    /// ```
+    /// use holochain_core_types::{
+    ///     chain_header::test_chain_header, entry::test_entry,
+    ///     network::{aspect_list::AspectList, entry_aspect::EntryAspect},
+    /// };
+    ///
+    /// let mut list = AspectList::new();
+    /// for _ in 0..3 {
+    ///     list.push(EntryAspect::Content(test_entry(), test_chain_header()));
+    /// }
+    /// assert_eq!(list.len(), 3);
+    ///
+    /// list.dedup();
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create an empty list that can hold `capacity` aspects without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        AspectList {
+            aspects: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.aspects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aspects.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.aspects.clear()
+    }
+
+    /// Append an aspect without checking for duplicates.
+    pub fn push(&mut self, aspect: EntryAspect) {
+        self.aspects.push(aspect)
+    }
+
+    /// Remove all aspects whose address was already seen earlier in the list,
+    /// keeping the first occurrence and the order of the remaining aspects.
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.aspects.retain(|aspect| seen.insert(aspect.address()));
+    }
+
+    /// Merge `other` into this list, keeping a single aspect out of all aspects
+    /// that are EntryAspect::content_eq(), e.g. the same entry published by two agents
+    /// or re-signed. Of those the one with the earliest header timestamp is kept, ties
+    /// go to the smaller aspect address, so that the result does not depend on which
+    /// list an aspect came from. Kept aspects are at the position of the first aspect of
+    /// their group, this list's aspects first.
+    ///
+    /// See dedup() for dropping aspects that have the same address.
+    pub fn merge_semantic(self, other: AspectList) -> AspectList {
+        fn precedes(a: &EntryAspect, b: &EntryAspect) -> bool {
+            (a.header().timestamp(), a.address()) < (b.header().timestamp(), b.address())
+        }
+        // content_eq() aspects always share their aspect type and the link base (link
+        // aspects) or the header's entry address (all others), so only the kept aspects
+        // of the same bucket need to be compared
+        fn bucket_key(aspect: &EntryAspect) -> (AspectType, Address) {
+            let address = match aspect.as_link() {
+                Some(link) => link.base(),
+                None => aspect.header().entry_address(),
+            };
+            (aspect.aspect_type(), address.clone())
+        }
+        let mut buckets: HashMap<(AspectType, Address), Vec<usize>> = HashMap::new();
+        let mut merged: Vec<EntryAspect> = Vec::with_capacity(self.len() + other.len());
+        for aspect in self.aspects.into_iter().chain(other.aspects) {
+            let bucket = buckets.entry(bucket_key(&aspect)).or_insert_with(Vec::new);
+            let found = bucket
+                .iter()
+                .cloned()
+                .find(|index| merged[*index].content_eq(&aspect));
+            match found {
+                Some(index) => {
+                    if precedes(&aspect, &merged[index]) {
+                        merged[index] = aspect;
+                    }
+                }
+                None => {
+                    bucket.push(merged.len());
+                    merged.push(aspect);
+                }
+            }
+        }
+        AspectList { aspects: merged }
+    }
+
+    /// The aspects whose address is not in `digest`, i.e. what a peer that only
+    /// sent us the addresses it holds is missing, in list order.
+    pub fn not_covered_by(&self, digest: &HashSet<Address>) -> Vec<&EntryAspect> {
+        self.aspects
+            .iter()
+            .filter(|aspect| !digest.contains(&aspect.address()))
+            .collect()
+    }
+
+    /// Anti-entropy difference to `other` as `(missing, extra)`: the addresses of the
+    /// aspects only `other` holds and of those only this list holds, each in list order
+    /// and without duplicates.
+    pub fn diff(&self, other: &AspectList) -> (Vec<Address>, Vec<Address>) {
+        fn addresses(aspects: Vec<&EntryAspect>) -> Vec<Address> {
+            aspects
+                .into_iter()
+                .map(AddressableContent::address)
+                .collect()
+        }
+        (
+            addresses(other.only_in(self)),
+            addresses(self.only_in(other)),
+        )
+    }
+
+    /// diff(), broken down by the type of the differing aspects.
+    /// This needs both lists to hold the full aspects; for a peer that only sent the
+    /// addresses it holds, see not_covered_by().
+    pub fn diff_detail(&self, other: &AspectList) -> DiffReport {
+        fn by_type(aspects: Vec<&EntryAspect>) -> BTreeMap<AspectType, Vec<Address>> {
+            let mut by_type = BTreeMap::new();
+            for aspect in aspects {
+                by_type
+                    .entry(aspect.aspect_type())
+                    .or_insert_with(Vec::new)
+                    .push(aspect.address());
+            }
+            by_type
+        }
+        DiffReport {
+            missing: by_type(other.only_in(self)),
+            extra: by_type(self.only_in(other)),
+        }
+    }
+
+    /// This list's aspects whose address `other` does not hold, first occurrences only.
+    fn only_in(&self, other: &AspectList) -> Vec<&EntryAspect> {
+        let held: HashSet<Address> = other.iter().map(AddressableContent::address).collect();
+        let mut seen = HashSet::new();
+        self.aspects
+            .iter()
+            .filter(|aspect| {
+                let address = aspect.address();
+                !held.contains(&address) && seen.insert(address)
+            })
+            .collect()
+    }
+
+    pub fn iter(&self) -> slice::Iter<EntryAspect> {
+        self.aspects.iter()
+    }
+
+    pub fn as_slice(&self) -> &[EntryAspect] {
+        &self.aspects
+    }
+
+    /// Calls `f` with every aspect in order, without cloning or collecting anything.
+    pub fn visit<F: FnMut(&EntryAspect)>(&self, mut f: F) {
+        for aspect in &self.aspects {
+            f(aspect)
+        }
+    }
+
+    /// Like visit(), but stops at and returns the first error of `f`.
+    pub fn try_visit<F: FnMut(&EntryAspect) -> Result<(), HolochainError>>(
+        &self,
+        mut f: F,
+    ) -> Result<(), HolochainError> {
+        for aspect in &self.aspects {
+            f(aspect)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of AspectList::diff_detail(): the addresses of the aspects we are missing
+/// and of those only we hold, by aspect type.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiffReport {
+    pub missing: BTreeMap<AspectType, Vec<Address>>,
+    pub extra: BTreeMap<AspectType, Vec<Address>>,
+}
+
+impl DiffReport {
+    /// How many aspects of `aspect_type` we are missing
+    pub fn missing_count(&self, aspect_type: AspectType) -> usize {
+        self.missing.get(&aspect_type).map_or(0, Vec::len)
+    }
+
+    /// How many aspects of `aspect_type` only we hold
+    pub fn extra_count(&self, aspect_type: AspectType) -> usize {
+        self.extra.get(&aspect_type).map_or(0, Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+impl From<Vec<EntryAspect>> for AspectList {
+    fn from(aspects: Vec<EntryAspect>) -> Self {
+        AspectList { aspects }
+    }
+}
+
+impl From<AspectList> for Vec<EntryAspect> {
+    fn from(list: AspectList) -> Self {
+        list.aspects
+    }
+}
+
+impl FromIterator<EntryAspect> for AspectList {
+    fn from_iter<I: IntoIterator<Item = EntryAspect>>(iter: I) -> Self {
+        AspectList {
+            aspects: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<EntryAspect> for AspectList {
+    fn extend<I: IntoIterator<Item = EntryAspect>>(&mut self, iter: I) {
+        self.aspects.extend(iter)
+    }
+}
+
+impl IntoIterator for AspectList {
+    type Item = EntryAspect;
+    type IntoIter = vec::IntoIter<EntryAspect>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.aspects.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AspectList {
+    type Item = &'a EntryAspect;
+    type IntoIter = slice::Iter<'a, EntryAspect>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.aspects.iter()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::{test_chain_header, test_chain_header_with_sig, ChainHeader},
+        entry::{test_entry, test_entry_b, Entry},
+        link::{example_link, link_data::LinkData},
+        network::entry_aspect::tests::{
+            test_content_aspect, test_deletion_aspect, test_header_for, test_link_add_aspect,
+        },
+        time::{test_iso_8601, Iso8601},
+    };
+
+    fn test_aspects() -> Vec<EntryAspect> {
+        vec![
+            EntryAspect::Content(test_entry(), test_chain_header()),
+            EntryAspect::Header(test_chain_header_with_sig("other sig", None)),
+            EntryAspect::Content(test_entry(), test_chain_header()),
+        ]
+    }
+
+    #[test]
+    fn empty_list_test() {
+        assert!(AspectList::new().is_empty());
+        assert_eq!(AspectList::new(), AspectList::default());
+        assert!(AspectList::with_capacity(10).is_empty());
+    }
+
+    #[test]
+    fn accumulate_and_clear_test() {
+        let mut list = AspectList::with_capacity(3);
+        list.extend(test_aspects());
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn dedup_test() {
+        let mut list: AspectList = test_aspects().into_iter().collect();
+        list.dedup();
+        assert_eq!(list.as_slice(), &test_aspects()[0..2]);
+    }
+
+    #[test]
+    fn merge_semantic_test() {
+        let ours = AspectList::from(vec![EntryAspect::Content(
+            test_entry(),
+            test_chain_header(),
+        )]);
+        let theirs = AspectList::from(vec![EntryAspect::Content(
+            test_entry(),
+            test_chain_header_with_sig("other sig", None),
+        )]);
+        let merged = ours.clone().merge_semantic(theirs.clone());
+        assert_eq!(merged.len(), 1);
+        // deterministic regardless of the order of the lists
+        assert_eq!(merged, theirs.merge_semantic(ours));
+
+        let earlier = EntryAspect::Content(
+            test_entry(),
+            test_header_for(&test_entry(), &Iso8601::from(1_000)),
+        );
+        let merged = AspectList::from(test_aspects())
+            .merge_semantic(AspectList::from(vec![earlier.clone()]));
+        assert_eq!(
+            merged.as_slice(),
+            &[
+                earlier,
+                EntryAspect::Header(test_chain_header_with_sig("other sig", None))
+            ]
+        );
+
+        // re-signed headers of the same entry
+        let merged = AspectList::from(vec![EntryAspect::Header(test_chain_header())])
+            .merge_semantic(AspectList::from(vec![EntryAspect::Header(
+                test_chain_header_with_sig("other sig", None),
+            )]));
+        assert_eq!(merged.len(), 1);
+
+        // the same link added on top of two chains: content_eq() but different entries
+        let link_add_on = |top: ChainHeader| {
+            let link_data = LinkData::add_from_link(&example_link(), top, test_agent_id());
+            let header = test_header_for(&Entry::LinkAdd(link_data.clone()), &test_iso_8601());
+            EntryAspect::LinkAdd(link_data, header)
+        };
+        let first = link_add_on(test_chain_header());
+        let second = link_add_on(test_chain_header_with_sig("other sig", None));
+        assert_ne!(
+            first.header().entry_address(),
+            second.header().entry_address()
+        );
+        let merged = AspectList::from(vec![first]).merge_semantic(AspectList::from(vec![second]));
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn not_covered_by_test() {
+        let list = AspectList::from(vec![
+            EntryAspect::Content(test_entry(), test_chain_header()),
+            EntryAspect::Header(test_chain_header()),
+            EntryAspect::Header(test_chain_header_with_sig("other sig", None)),
+        ]);
+        let mut digest: HashSet<Address> = list.iter().map(|aspect| aspect.address()).collect();
+        assert!(list.not_covered_by(&digest).is_empty());
+
+        digest.remove(&list.as_slice()[1].address());
+        assert_eq!(list.not_covered_by(&digest), vec![&list.as_slice()[1]]);
+    }
+
+    #[test]
+    fn diff_detail_test() {
+        let ours = AspectList::from(vec![test_content_aspect(), test_link_add_aspect()]);
+        let theirs = AspectList::from(vec![
+            test_link_add_aspect(),
+            test_deletion_aspect(),
+            EntryAspect::Content(
+                test_entry_b(),
+                test_header_for(&test_entry_b(), &test_iso_8601()),
+            ),
+            test_deletion_aspect(),
+        ]);
+
+        let (missing, extra) = ours.diff(&theirs);
+        assert_eq!(missing.len(), 2);
+        assert_eq!(extra, vec![test_content_aspect().address()]);
+
+        let report = ours.diff_detail(&theirs);
+        assert_eq!(report.missing_count(AspectType::Deletion), 1);
+        assert_eq!(report.missing_count(AspectType::Content), 1);
+        assert_eq!(report.missing_count(AspectType::LinkAdd), 0);
+        assert_eq!(report.extra_count(AspectType::Content), 1);
+        assert_eq!(
+            report.missing[&AspectType::Deletion],
+            vec![test_deletion_aspect().address()]
+        );
+
+        assert!(ours.diff_detail(&ours.clone()).is_empty());
+    }
+
+    #[test]
+    fn visit_test() {
+        let list = AspectList::from(test_aspects());
+        let mut visited = Vec::new();
+        list.visit(|aspect| visited.push(aspect.address()));
+        let expected: Vec<Address> = test_aspects().iter().map(|a| a.address()).collect();
+        assert_eq!(visited, expected);
+
+        let mut count = 0;
+        let result = list.try_visit(|_| {
+            count += 1;
+            if count == 2 {
+                Err(HolochainError::ErrorGeneric(String::from("stop")))
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(
+            result,
+            Err(HolochainError::ErrorGeneric(String::from("stop")))
+        );
+        assert_eq!(count, 2);
+        assert_eq!(list.try_visit(|_| Ok(())), Ok(()));
+    }
+}
@@ -0,0 +1,69 @@
+//! Newline-delimited JSON (NDJSON / JSON Lines) encoding of EntryAspects:
+//! one aspect's JSON per line, for logs and tools that consume them.
+
+use crate::{error::HolochainError, network::entry_aspect::EntryAspect};
+use holochain_json_api::json::JsonString;
+use holochain_persistence_api::cas::content::AddressableContent;
+use std::{
+    convert::TryFrom,
+    io::{BufRead, BufReader, Read, Write},
+};
+
+/// Writes every aspect as a single line of JSON.
+pub fn write_ndjson<W: Write>(
+    aspects: &[EntryAspect],
+    mut writer: W,
+) -> Result<(), HolochainError> {
+    for aspect in aspects {
+        writeln!(writer, "{}", String::from(aspect.content()))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads aspects line by line. Blank lines are skipped, a line that is not a valid
+/// aspect yields an `Err` for that line and reading carries on with the next one.
+pub fn read_ndjson<R: Read>(
+    reader: R,
+) -> impl Iterator<Item = Result<EntryAspect, HolochainError>> {
+    BufReader::new(reader)
+        .lines()
+        .filter_map(|line| match line {
+            Ok(ref line) if line.trim().is_empty() => None,
+            Ok(line) => Some(
+                EntryAspect::try_from(JsonString::from_json(&line)).map_err(HolochainError::from),
+            ),
+            Err(error) => Some(Err(HolochainError::from(error))),
+        })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::network::entry_aspect::tests::{test_content_aspect, test_link_add_aspect};
+
+    #[test]
+    fn ndjson_round_trip_test() {
+        let aspects = vec![test_content_aspect(), test_link_add_aspect()];
+        let mut buffer = Vec::new();
+        write_ndjson(&aspects, &mut buffer).unwrap();
+        assert_eq!(buffer.iter().filter(|byte| **byte == b'\n').count(), 2);
+
+        let read: Result<Vec<EntryAspect>, HolochainError> = read_ndjson(&buffer[..]).collect();
+        assert_eq!(read, Ok(aspects));
+    }
+
+    #[test]
+    fn read_ndjson_malformed_line_test() {
+        let mut buffer = Vec::new();
+        write_ndjson(&[test_content_aspect()], &mut buffer).unwrap();
+        buffer.extend_from_slice(b"\n{\"Content\": \"not an aspect\"}\n   \n");
+        write_ndjson(&[test_link_add_aspect()], &mut buffer).unwrap();
+
+        let read: Vec<Result<EntryAspect, HolochainError>> = read_ndjson(&buffer[..]).collect();
+        assert_eq!(read.len(), 3);
+        assert_eq!(read[0], Ok(test_content_aspect()));
+        assert!(read[1].is_err());
+        assert_eq!(read[2], Ok(test_link_add_aspect()));
+    }
+}
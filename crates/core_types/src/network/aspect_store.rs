@@ -0,0 +1,278 @@
+//! AspectStore is the storage seam for code that handles EntryAspects,
+//! so that it does not have to depend on a concrete content addressable storage.
+//! MemoryAspectStore keeps aspects in a HashMap (e.g. for tests),
+//! CasAspectStore stores them in a ContentAddressableStorage.
+//! AspectCache is a bounded LRU cache in front of any AspectStore.
+
+use crate::{error::HolochainError, network::entry_aspect::EntryAspect};
+use holochain_locksmith::RwLock;
+use holochain_persistence_api::cas::{
+    content::{Address, AddressableContent},
+    storage::ContentAddressableStorage,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::Arc,
+};
+
+pub trait AspectStore {
+    /// Store the aspect, returning its address
+    fn put(&mut self, aspect: EntryAspect) -> Result<Address, HolochainError>;
+
+    /// Get the aspect with the given address, if it got stored before
+    fn get(&self, address: &Address) -> Result<Option<EntryAspect>, HolochainError>;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryAspectStore {
+    aspects: HashMap<Address, EntryAspect>,
+}
+
+impl MemoryAspectStore {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.aspects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aspects.is_empty()
+    }
+}
+
+impl AspectStore for MemoryAspectStore {
+    fn put(&mut self, aspect: EntryAspect) -> Result<Address, HolochainError> {
+        let address = aspect.address();
+        self.aspects.insert(address.clone(), aspect);
+        Ok(address)
+    }
+
+    fn get(&self, address: &Address) -> Result<Option<EntryAspect>, HolochainError> {
+        Ok(self.aspects.get(address).cloned())
+    }
+}
+
+/// AspectStore on top of a shared ContentAddressableStorage.
+#[derive(Clone)]
+pub struct CasAspectStore {
+    cas: Arc<RwLock<dyn ContentAddressableStorage>>,
+}
+
+impl CasAspectStore {
+    pub fn new(cas: Arc<RwLock<dyn ContentAddressableStorage>>) -> Self {
+        CasAspectStore { cas }
+    }
+}
+
+impl fmt::Debug for CasAspectStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CasAspectStore")
+    }
+}
+
+impl AspectStore for CasAspectStore {
+    fn put(&mut self, aspect: EntryAspect) -> Result<Address, HolochainError> {
+        self.cas.write()?.add(&aspect)?;
+        Ok(aspect.address())
+    }
+
+    fn get(&self, address: &Address) -> Result<Option<EntryAspect>, HolochainError> {
+        match self.cas.read()?.fetch(address)? {
+            Some(content) => Ok(Some(EntryAspect::try_from_content(&content)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Capacity-bounded cache of aspects by address that evicts the least
+/// recently used aspect when it overflows. Both get() and put() count as use.
+#[derive(Clone, Debug)]
+pub struct AspectCache {
+    capacity: usize,
+    tick: u64,
+    aspects: HashMap<Address, (u64, EntryAspect)>,
+    // last use -> address, the first entry is the least recently used
+    recency: BTreeMap<u64, Address>,
+}
+
+impl AspectCache {
+    pub fn new(capacity: usize) -> Self {
+        AspectCache {
+            capacity,
+            tick: 0,
+            aspects: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.aspects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aspects.is_empty()
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    pub fn get(&mut self, address: &Address) -> Option<EntryAspect> {
+        let tick = self.next_tick();
+        let (last_use, aspect) = self.aspects.get_mut(address)?;
+        self.recency.remove(&*last_use);
+        *last_use = tick;
+        self.recency.insert(tick, address.clone());
+        Some(aspect.clone())
+    }
+
+    /// Caches the aspect under its address, evicting the least recently used
+    /// aspect if the cache is full.
+    pub fn put(&mut self, aspect: EntryAspect) -> Address {
+        let address = aspect.address();
+        if self.capacity == 0 {
+            return address;
+        }
+        let tick = self.next_tick();
+        if let Some((last_use, _)) = self.aspects.insert(address.clone(), (tick, aspect)) {
+            self.recency.remove(&last_use);
+        }
+        self.recency.insert(tick, address.clone());
+
+        while self.aspects.len() > self.capacity {
+            let oldest = *self
+                .recency
+                .keys()
+                .next()
+                .expect("recency has an entry for every cached aspect");
+            if let Some(evicted) = self.recency.remove(&oldest) {
+                self.aspects.remove(&evicted);
+            }
+        }
+        address
+    }
+
+    /// Read-through look-up: answers from the cache if possible, otherwise
+    /// gets the aspect from `store` and caches it.
+    pub fn get_or_fetch(
+        &mut self,
+        address: &Address,
+        store: &impl AspectStore,
+    ) -> Result<Option<EntryAspect>, HolochainError> {
+        if let Some(aspect) = self.get(address) {
+            return Ok(Some(aspect));
+        }
+        let maybe_aspect = store.get(address)?;
+        if let Some(aspect) = &maybe_aspect {
+            self.put(aspect.clone());
+        }
+        Ok(maybe_aspect)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::test_chain_header,
+        link::{example_link, link_data::LinkData},
+        network::entry_aspect::tests::{
+            test_content_aspect, test_deletion_aspect, test_link_add_aspect, test_update_aspect,
+        },
+    };
+    use holochain_persistence_api::cas::storage::test_content_addressable_storage;
+
+    /// one aspect of each variant
+    pub fn test_aspects() -> Vec<EntryAspect> {
+        let removal =
+            LinkData::remove_from_link(&example_link(), test_chain_header(), test_agent_id());
+        vec![
+            test_content_aspect(),
+            EntryAspect::Header(test_chain_header()),
+            test_link_add_aspect(),
+            EntryAspect::LinkRemove(
+                (
+                    removal,
+                    vec![test_link_add_aspect().header().entry_address().clone()],
+                ),
+                test_chain_header(),
+            ),
+            test_update_aspect(),
+            test_deletion_aspect(),
+        ]
+    }
+
+    fn round_trip(store: &mut impl AspectStore) {
+        for aspect in test_aspects() {
+            let address = store.put(aspect.clone()).expect("could not put aspect");
+            assert_eq!(address, aspect.address());
+            assert_eq!(store.get(&address), Ok(Some(aspect)));
+        }
+        assert_eq!(
+            store.get(&Address::from(String::from("QmMissing"))),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn memory_aspect_store_test() {
+        let mut store = MemoryAspectStore::new();
+        round_trip(&mut store);
+        assert_eq!(store.len(), test_aspects().len());
+    }
+
+    #[test]
+    fn cas_aspect_store_test() {
+        let cas = Arc::new(RwLock::new(test_content_addressable_storage()));
+        round_trip(&mut CasAspectStore::new(cas));
+    }
+
+    #[test]
+    fn aspect_cache_eviction_test() {
+        let aspects = test_aspects();
+        let mut cache = AspectCache::new(2);
+        let first = cache.put(aspects[0].clone());
+        let second = cache.put(aspects[1].clone());
+
+        // using the first makes the second the least recently used
+        assert_eq!(cache.get(&first), Some(aspects[0].clone()));
+        let third = cache.put(aspects[2].clone());
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&second), None);
+        assert_eq!(cache.get(&first), Some(aspects[0].clone()));
+        assert_eq!(cache.get(&third), Some(aspects[2].clone()));
+
+        let mut no_cache = AspectCache::new(0);
+        no_cache.put(aspects[0].clone());
+        assert!(no_cache.is_empty());
+    }
+
+    #[test]
+    fn aspect_cache_read_through_test() {
+        let aspects = test_aspects();
+        let mut store = MemoryAspectStore::new();
+        let address = store.put(aspects[0].clone()).unwrap();
+        let mut cache = AspectCache::new(2);
+
+        assert_eq!(cache.get(&address), None);
+        assert_eq!(
+            cache.get_or_fetch(&address, &store),
+            Ok(Some(aspects[0].clone()))
+        );
+        assert_eq!(cache.get(&address), Some(aspects[0].clone()));
+
+        let missing = aspects[1].address();
+        assert_eq!(cache.get_or_fetch(&missing, &store), Ok(None));
+        assert_eq!(cache.len(), 1);
+    }
+}
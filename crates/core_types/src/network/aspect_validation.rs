@@ -0,0 +1,701 @@
+//! Validation of EntryAspects received from the network.
+//! This is the check a node runs before holding an aspect: the aspect has
+//! to be consistent in itself and everything it depends on has to be known
+//! locally, which is looked up through an AspectResolver.
+//! ValidationCache memoizes the verdicts of validations that can't change.
+
+use crate::{
+    entry::{entry_type::EntryType, Entry},
+    error::{ErrorKind, HolochainError},
+    link::LinkActionKind,
+    network::{
+        aspect_list::AspectList,
+        entry_aspect::{AspectType, EntryAspect},
+    },
+};
+use holochain_persistence_api::cas::content::{Address, AddressableContent};
+use std::collections::{BTreeMap, HashMap};
+
+/// Look-up of the data an aspect's validation depends on.
+pub trait AspectResolver {
+    /// An aspect whose header is the header of the entry at `address`.
+    fn get_aspect(&self, address: &Address) -> Option<EntryAspect>;
+
+    /// Like get_aspect(), but tells why there is none: `NotFound` if the entry at
+    /// `address` is not known (yet), any other error if it can never be resolved.
+    /// Validation takes the former for a dependency that may still arrive and
+    /// the latter for a permanent failure.
+    fn resolve_aspect(&self, address: &Address) -> Result<EntryAspect, HolochainError> {
+        self.get_aspect(address)
+            .ok_or_else(|| HolochainError::NotFound {
+                address: address.clone(),
+            })
+    }
+
+    /// The type of the entry at `address`.
+    fn get_entry_type(&self, address: &Address) -> Option<EntryType> {
+        self.get_aspect(address)
+            .map(|aspect| aspect.header().entry_type().clone())
+    }
+}
+
+/// AspectResolver that is backed by a HashMap, mainly for tests.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryAspectResolver {
+    aspects: HashMap<Address, EntryAspect>,
+}
+
+impl InMemoryAspectResolver {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Makes `aspect` resolvable under the entry address of its header.
+    pub fn insert(&mut self, aspect: EntryAspect) {
+        self.aspects
+            .insert(aspect.header().entry_address().clone(), aspect);
+    }
+}
+
+impl AspectResolver for InMemoryAspectResolver {
+    fn get_aspect(&self, address: &Address) -> Option<EntryAspect> {
+        self.aspects.get(address).cloned()
+    }
+}
+
+/// Turns a list of validation failures into a single result.
+fn collect_failures(mut failures: Vec<HolochainError>) -> Result<(), HolochainError> {
+    match failures.len() {
+        0 => Ok(()),
+        1 => Err(failures.remove(0)),
+        _ => Err(HolochainError::List(failures)),
+    }
+}
+
+fn failure(message: String) -> HolochainError {
+    HolochainError::ValidationFailed(message)
+}
+
+impl EntryAspect {
+    /// Checks that do not need anything but the aspect itself:
+    /// * the header is the header of the contained entry (`Content`, `Update`,
+    ///   `LinkAdd` and `LinkRemove`)
+    /// * the entry of `Content` and `Update` is of the header's entry type
+    ///   (see content_type_consistent())
+    /// * link data has the action kind that matches the variant
+    /// * `Update` and `Deletion` headers carry a CRUD link that does not point at
+    ///   their own entry (see has_crud_cycle())
+    /// * if `reject_self_links` is set, link aspects must not be self links (see is_self_link())
+    pub fn validate_self_consistency(&self, reject_self_links: bool) -> Result<(), HolochainError> {
+        let header = self.header();
+        let mut failures = Vec::new();
+
+        let contained_entry = match self {
+            EntryAspect::Content(entry, _) | EntryAspect::Update(entry, _) => Some(entry.clone()),
+            EntryAspect::LinkAdd(link_data, _) => Some(Entry::LinkAdd(link_data.clone())),
+            EntryAspect::LinkRemove(removal, _) => Some(Entry::LinkRemove(removal.clone())),
+            EntryAspect::Header(_) | EntryAspect::Deletion(_) => None,
+        };
+        if let Some(entry) = contained_entry {
+            if entry.address() != *header.entry_address() {
+                failures.push(failure(format!(
+                    "{} aspect: header is for entry {} but contains entry {}",
+                    self.type_hint(),
+                    header.entry_address(),
+                    entry.address()
+                )));
+            }
+        }
+        if let EntryAspect::Content(entry, _) | EntryAspect::Update(entry, _) = self {
+            if !self.content_type_consistent() {
+                failures.push(failure(format!(
+                    "{} aspect: header is for a {} entry but contains a {} entry",
+                    self.type_hint(),
+                    header.entry_type(),
+                    entry.entry_type()
+                )));
+            }
+        }
+
+        match self {
+            EntryAspect::LinkAdd(link_data, _)
+                if *link_data.action_kind() != LinkActionKind::ADD =>
+            {
+                failures.push(failure(String::from(
+                    "link_add aspect: link data is not an ADD action",
+                )))
+            }
+            EntryAspect::LinkRemove((link_data, _), _)
+                if *link_data.action_kind() != LinkActionKind::REMOVE =>
+            {
+                failures.push(failure(String::from(
+                    "link_remove aspect: link data is not a REMOVE action",
+                )))
+            }
+            EntryAspect::Update(_, _) | EntryAspect::Deletion(_)
+                if header.link_update_delete().is_none() =>
+            {
+                failures.push(failure(format!(
+                    "{} aspect: header has no CRUD link",
+                    self.type_hint()
+                )))
+            }
+            _ => (),
+        }
+
+        if self.has_crud_cycle() {
+            failures.push(failure(format!(
+                "{} aspect: CRUD link of entry {} points to itself",
+                self.type_hint(),
+                header.entry_address()
+            )));
+        }
+
+        if reject_self_links && self.is_self_link() {
+            failures.push(failure(format!(
+                "{} aspect: link from {} to itself",
+                self.type_hint(),
+                self.entry_address()?
+            )));
+        }
+
+        collect_failures(failures)
+    }
+
+    /// Full validation of this aspect against what `resolver` knows.
+    /// Runs all of the following and reports every failure, not just the first:
+    /// * validate_self_consistency(), with self links allowed
+    /// * the header's entry type fits the variant (e.g. `link_add` for `LinkAdd`,
+    ///   an app type for `Update`)
+    /// * every address in dependencies() resolves, see AspectResolver::resolve_aspect()
+    ///   (a `NotFound` failure if it is not known yet)
+    /// * the addresses a `LinkRemove` removes are `LinkAdd` entries
+    ///
+    /// See validate_with() for the optional checks.
+    pub fn validate(&self, resolver: &impl AspectResolver) -> Result<(), HolochainError> {
+        self.validate_with(resolver, &ValidationOptions::default())
+    }
+
+    /// validate(), plus the optional checks enabled in `options`.
+    pub fn validate_with(
+        &self,
+        resolver: &impl AspectResolver,
+        options: &ValidationOptions,
+    ) -> Result<(), HolochainError> {
+        collect_failures(self.validation_failures(resolver, options).0)
+    }
+
+    /// For an `Update`, whether the new entry has the same type as the base entry it
+    /// replaces. Errors if the header has no CRUD link or `resolver` does not know
+    /// the base. Always true for other aspects.
+    pub fn update_type_consistent(
+        &self,
+        resolver: &impl AspectResolver,
+    ) -> Result<bool, HolochainError> {
+        match self {
+            EntryAspect::Update(entry, _) => {
+                let base = self.entry_address()?;
+                let base_type =
+                    resolver
+                        .get_entry_type(&base)
+                        .ok_or_else(|| HolochainError::NotFound {
+                            address: base.clone(),
+                        })?;
+                Ok(base_type == entry.entry_type())
+            }
+            _ => Ok(true),
+        }
+    }
+
+    /// All failures of validate_with(), plus whether any of them is due to `resolver`
+    /// not knowing a dependency (yet), i.e. whether the verdict may change later.
+    fn validation_failures(
+        &self,
+        resolver: &impl AspectResolver,
+        options: &ValidationOptions,
+    ) -> (Vec<HolochainError>, bool) {
+        let mut failures = Vec::new();
+        let mut unresolved = false;
+
+        if let Err(error) = self.validate_self_consistency(false) {
+            match error {
+                HolochainError::List(errors) => failures.extend(errors),
+                error => failures.push(error),
+            }
+        }
+
+        let entry_type = self.header().entry_type();
+        let type_fits = match self {
+            EntryAspect::Content(_, _) | EntryAspect::Header(_) => true,
+            EntryAspect::LinkAdd(_, _) => *entry_type == EntryType::LinkAdd,
+            EntryAspect::LinkRemove(_, _) => *entry_type == EntryType::LinkRemove,
+            EntryAspect::Update(_, _) => entry_type.is_app(),
+            EntryAspect::Deletion(_) => *entry_type == EntryType::Deletion,
+        };
+        if !type_fits {
+            failures.push(failure(format!(
+                "{} aspect can not have a header of entry type {}",
+                self.type_hint(),
+                entry_type
+            )));
+        }
+
+        for dependency in self.dependencies() {
+            if let Err(error) = resolver.resolve_aspect(&dependency) {
+                unresolved |= error.kind() == ErrorKind::NotFound;
+                failures.push(error);
+            }
+        }
+
+        if let EntryAspect::LinkRemove((_, removed), _) = self {
+            for address in removed {
+                match resolver.get_entry_type(address) {
+                    Some(EntryType::LinkAdd) | None => (),
+                    Some(other) => failures.push(failure(format!(
+                        "link_remove aspect removes {} which is a {} entry, not a link_add",
+                        address, other
+                    ))),
+                }
+            }
+        }
+
+        // a missing CRUD link or unresolved base got reported above already
+        if options.check_update_types && self.update_type_consistent(resolver) == Ok(false) {
+            failures.push(failure(format!(
+                "update aspect: entry type {} differs from the type of its base",
+                self.header().entry_type()
+            )));
+        }
+
+        (failures, unresolved)
+    }
+}
+
+/// Checks of EntryAspect::validate_with() that are off by default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// Reject `Update` aspects whose entry type differs from the type of the
+    /// entry they update, see EntryAspect::update_type_consistent().
+    pub check_update_types: bool,
+}
+
+/// Memoizes the verdicts of EntryAspect::validate() by validation_fingerprint(),
+/// so that validating an equivalent aspect again is a single look-up.
+///
+/// Only verdicts that can't change are cached:
+/// * Successes, and failures that follow from the aspect's data alone.
+///   Aspects are content addressed, so these stay true; no invalidation needed.
+/// * NOT failures caused by a dependency the resolver could not resolve. That
+///   might just not have arrived yet, so such aspects get validated again next time.
+///
+/// The fingerprint does not cover everything validate_self_consistency() checks
+/// (e.g. the contained entry vs. the header), so that check runs on every call
+/// and only self-consistent aspects are looked up in the cache.
+#[derive(Clone, Debug, Default)]
+pub struct ValidationCache {
+    verdicts: HashMap<Address, Result<(), HolochainError>>,
+}
+
+impl ValidationCache {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Number of cached verdicts
+    pub fn len(&self) -> usize {
+        self.verdicts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.verdicts.is_empty()
+    }
+
+    /// Same result as `aspect.validate(resolver)`, answered from the cache if possible.
+    pub fn validate(
+        &mut self,
+        aspect: &EntryAspect,
+        resolver: &impl AspectResolver,
+    ) -> Result<(), HolochainError> {
+        if aspect.validate_self_consistency(false).is_err() {
+            return aspect.validate(resolver);
+        }
+        let fingerprint = aspect.validation_fingerprint()?;
+        if let Some(verdict) = self.verdicts.get(&fingerprint) {
+            return verdict.clone();
+        }
+        let (failures, unresolved) =
+            aspect.validation_failures(resolver, &ValidationOptions::default());
+        let verdict = collect_failures(failures);
+        if !unresolved {
+            self.verdicts.insert(fingerprint, verdict.clone());
+        }
+        verdict
+    }
+}
+
+impl AspectList {
+    /// Runs EntryAspect::validate() on every aspect of the list, without stopping at
+    /// the first invalid one, e.g. to gate the ingestion of a batch.
+    pub fn validate_all(&self, resolver: &impl AspectResolver) -> ValidationReport {
+        let options = ValidationOptions::default();
+        let results = self
+            .iter()
+            .map(|aspect| {
+                let (failures, unresolved) = aspect.validation_failures(resolver, &options);
+                AspectValidation {
+                    address: aspect.address(),
+                    aspect_type: aspect.aspect_type(),
+                    result: collect_failures(failures),
+                    unresolved,
+                }
+            })
+            .collect();
+        ValidationReport { results }
+    }
+}
+
+/// The verdict on a single aspect of an AspectList::validate_all().
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AspectValidation {
+    pub address: Address,
+    pub aspect_type: AspectType,
+    pub result: Result<(), HolochainError>,
+    /// Whether a dependency could not be resolved, so the aspect may pass once it arrives
+    pub unresolved: bool,
+}
+
+/// The result of AspectList::validate_all(), in list order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    results: Vec<AspectValidation>,
+}
+
+impl ValidationReport {
+    pub fn results(&self) -> &[AspectValidation] {
+        &self.results
+    }
+
+    pub fn all_valid(&self) -> bool {
+        self.results
+            .iter()
+            .all(|validation| validation.result.is_ok())
+    }
+
+    /// The verdicts of the aspects that did not pass
+    pub fn failures(&self) -> Vec<&AspectValidation> {
+        self.results
+            .iter()
+            .filter(|validation| validation.result.is_err())
+            .collect()
+    }
+
+    pub fn passed_count(&self) -> usize {
+        self.results.len() - self.failed_count()
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.failures().len()
+    }
+
+    /// Number of aspects that did not pass, by aspect type
+    pub fn failure_counts(&self) -> BTreeMap<AspectType, usize> {
+        let mut counts = BTreeMap::new();
+        for validation in self.failures() {
+            *counts.entry(validation.aspect_type).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::{
+            test_chain_header, test_chain_header_with_sig, test_provenances, ChainHeader,
+        },
+        entry::{entry_type::AppEntryType, test_entry, test_entry_b},
+        link::{example_link, link_data::LinkData},
+        network::{
+            aspect_batch::tests::test_update_of,
+            entry_aspect::tests::{
+                test_content_aspect, test_deletion_aspect, test_header_for, test_link_add_aspect,
+                test_update_aspect,
+            },
+        },
+        time::test_iso_8601,
+    };
+
+    /// resolver that knows the base and target of `test_link_add_aspect()`
+    /// and the link add entry itself
+    pub fn test_resolver() -> InMemoryAspectResolver {
+        let mut resolver = InMemoryAspectResolver::new();
+        resolver.insert(test_content_aspect());
+        resolver.insert(EntryAspect::Content(
+            test_entry_b(),
+            test_header_for(&test_entry_b(), &test_iso_8601()),
+        ));
+        resolver.insert(test_link_add_aspect());
+        resolver
+    }
+
+    #[test]
+    fn validate_self_consistency_test() {
+        assert!(test_content_aspect()
+            .validate_self_consistency(false)
+            .is_ok());
+        assert!(test_link_add_aspect()
+            .validate_self_consistency(false)
+            .is_ok());
+        assert!(test_update_aspect()
+            .validate_self_consistency(false)
+            .is_ok());
+        assert!(test_deletion_aspect()
+            .validate_self_consistency(false)
+            .is_ok());
+
+        let mismatch = EntryAspect::Content(test_entry_b(), test_chain_header());
+        assert!(mismatch.validate_self_consistency(false).is_err());
+        let no_crud_link = EntryAspect::Deletion(test_chain_header());
+        assert!(no_crud_link.validate_self_consistency(false).is_err());
+        let wrong_type = EntryAspect::Content(
+            test_entry(),
+            ChainHeader::new(
+                &EntryType::LinkAdd,
+                &test_entry().address(),
+                &test_provenances("sig"),
+                &None,
+                &None,
+                &None,
+                &test_iso_8601(),
+            ),
+        );
+        assert!(wrong_type.validate_self_consistency(false).is_err());
+    }
+
+    #[test]
+    fn validate_self_consistency_crud_cycle_test() {
+        // an update of "b" whose CRUD link points at the "b" entry itself
+        let b = test_update_of(&test_entry().address(), "b");
+        let cycle = test_update_of(b.header().entry_address(), "b");
+        assert!(cycle.has_crud_cycle());
+        assert!(cycle.validate_self_consistency(false).is_err());
+        assert!(test_update_aspect()
+            .validate_self_consistency(false)
+            .is_ok());
+    }
+
+    #[test]
+    fn validate_self_consistency_self_link_test() {
+        let base = test_entry().address();
+        let link_data = LinkData::new_add(
+            &base,
+            &base,
+            "foo-link-tag",
+            "foo-link-type",
+            test_chain_header(),
+            test_agent_id(),
+        );
+        let self_link = EntryAspect::LinkAdd(
+            link_data.clone(),
+            test_header_for(&Entry::LinkAdd(link_data), &test_iso_8601()),
+        );
+        assert!(self_link.validate_self_consistency(false).is_ok());
+        assert!(self_link.validate_self_consistency(true).is_err());
+        assert!(test_link_add_aspect()
+            .validate_self_consistency(true)
+            .is_ok());
+    }
+
+    #[test]
+    fn validate_test() {
+        let resolver = test_resolver();
+        assert_eq!(test_content_aspect().validate(&resolver), Ok(()));
+        assert_eq!(test_link_add_aspect().validate(&resolver), Ok(()));
+        assert_eq!(test_update_aspect().validate(&resolver), Ok(()));
+
+        // missing dependencies
+        assert!(test_link_add_aspect()
+            .validate(&InMemoryAspectResolver::new())
+            .is_err());
+    }
+
+    /// resolver that knows nothing and rejects `rejected` for good
+    struct RejectingResolver {
+        rejected: Address,
+    }
+
+    impl AspectResolver for RejectingResolver {
+        fn get_aspect(&self, _address: &Address) -> Option<EntryAspect> {
+            None
+        }
+
+        fn resolve_aspect(&self, address: &Address) -> Result<EntryAspect, HolochainError> {
+            if *address == self.rejected {
+                Err(failure(format!("{} is invalid", address)))
+            } else {
+                Err(HolochainError::NotFound {
+                    address: address.clone(),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn validate_not_found_test() {
+        // the base of the update is a dependency
+        let base = test_entry().address();
+        let not_found = test_update_aspect().validate(&InMemoryAspectResolver::new());
+        assert_eq!(
+            not_found,
+            Err(HolochainError::NotFound {
+                address: base.clone()
+            })
+        );
+        assert_eq!(not_found.unwrap_err().kind(), ErrorKind::NotFound);
+
+        let rejecting = RejectingResolver { rejected: base };
+        let rejected = test_update_aspect().validate(&rejecting).unwrap_err();
+        assert_eq!(rejected.kind(), ErrorKind::Inconsistent);
+        let report = AspectList::from(vec![test_update_aspect()]).validate_all(&rejecting);
+        assert!(!report.results()[0].unresolved);
+
+        let rejecting = RejectingResolver {
+            rejected: test_entry_b().address(),
+        };
+        let report = AspectList::from(vec![test_update_aspect()]).validate_all(&rejecting);
+        assert!(report.results()[0].unresolved);
+    }
+
+    #[test]
+    fn validate_accumulates_failures_test() {
+        // wrong entry type for a deletion and no CRUD link
+        let deletion = EntryAspect::Deletion(test_header_for(&test_entry(), &test_iso_8601()));
+        match deletion.validate(&InMemoryAspectResolver::new()) {
+            Err(HolochainError::List(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("expected a list of failures, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_link_remove_targets_test() {
+        let link_data =
+            LinkData::remove_from_link(&example_link(), test_chain_header(), test_agent_id());
+        let link_remove = |removed: Address| {
+            let removal = (link_data.clone(), vec![removed]);
+            EntryAspect::LinkRemove(
+                removal.clone(),
+                test_header_for(&Entry::LinkRemove(removal), &test_iso_8601()),
+            )
+        };
+
+        let link_add_address = test_link_add_aspect().header().entry_address().clone();
+        let valid = link_remove(link_add_address);
+        let removes_app_entry = link_remove(test_entry().address());
+        assert_eq!(valid.validate(&test_resolver()), Ok(()));
+        assert!(removes_app_entry.validate(&test_resolver()).is_err());
+    }
+
+    #[test]
+    fn validation_cache_test() {
+        let mut cache = ValidationCache::new();
+        let resolver = test_resolver();
+
+        assert_eq!(cache.validate(&test_link_add_aspect(), &resolver), Ok(()));
+        assert_eq!(cache.len(), 1);
+        // hit, with a resolver that would not know the dependencies
+        assert_eq!(
+            cache.validate(&test_link_add_aspect(), &InMemoryAspectResolver::new()),
+            Ok(())
+        );
+        assert_eq!(cache.len(), 1);
+
+        // deterministic failure: a deletion header of the wrong type
+        let wrong_type = EntryAspect::Deletion(test_chain_header_with_sig(
+            "sig",
+            Some(test_entry_b().address()),
+        ));
+        let verdict = wrong_type.validate(&resolver);
+        assert!(verdict.is_err());
+        assert_eq!(cache.validate(&wrong_type, &resolver), verdict);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.validate(&wrong_type, &resolver), verdict);
+    }
+
+    #[test]
+    fn validation_cache_does_not_cache_resolver_misses_test() {
+        let mut cache = ValidationCache::new();
+        assert!(cache
+            .validate(&test_update_aspect(), &InMemoryAspectResolver::new())
+            .is_err());
+        assert!(cache.is_empty());
+        // once the dependency is known the aspect validates
+        assert_eq!(
+            cache.validate(&test_update_aspect(), &test_resolver()),
+            Ok(())
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn update_type_consistent_test() {
+        // base of test_update_aspect(), but as an entry of another type
+        let other_type_header = ChainHeader::new(
+            &EntryType::App(AppEntryType::from("otherEntryType")),
+            &test_entry().address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &None,
+            &test_iso_8601(),
+        );
+        let mut mismatched = InMemoryAspectResolver::new();
+        mismatched.insert(EntryAspect::Header(other_type_header));
+
+        let update = test_update_aspect();
+        assert_eq!(update.update_type_consistent(&test_resolver()), Ok(true));
+        assert_eq!(update.update_type_consistent(&mismatched), Ok(false));
+        assert!(update
+            .update_type_consistent(&InMemoryAspectResolver::new())
+            .is_err());
+        assert_eq!(
+            test_content_aspect().update_type_consistent(&mismatched),
+            Ok(true)
+        );
+
+        let options = ValidationOptions {
+            check_update_types: true,
+        };
+        assert_eq!(update.validate(&mismatched), Ok(()));
+        assert!(update.validate_with(&mismatched, &options).is_err());
+        assert_eq!(update.validate_with(&test_resolver(), &options), Ok(()));
+    }
+
+    #[test]
+    fn validate_all_test() {
+        // the resolver does not know the entry the update updates
+        let list = AspectList::from(vec![test_content_aspect(), test_update_aspect()]);
+        let report = list.validate_all(&InMemoryAspectResolver::new());
+
+        assert!(!report.all_valid());
+        assert_eq!(report.passed_count(), 1);
+        assert_eq!(report.failed_count(), 1);
+        assert_eq!(report.failure_counts().get(&AspectType::Update), Some(&1));
+        assert_eq!(report.failure_counts().get(&AspectType::Content), None);
+
+        let failure = report.failures()[0];
+        assert_eq!(failure.address, test_update_aspect().address());
+        assert!(failure.unresolved);
+        assert_eq!(
+            failure.result.clone().unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            report.results()[0].result,
+            test_content_aspect().validate(&InMemoryAspectResolver::new())
+        );
+
+        assert!(list.validate_all(&test_resolver()).all_valid());
+    }
+}
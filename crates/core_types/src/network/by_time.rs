@@ -0,0 +1,81 @@
+//! ByTime orders aspects chronologically, e.g. for showing a history:
+//! by the timestamp of their header, aspects with the same timestamp by
+//! their address so that the order is total and the same on every node.
+//! Every aspect has a header, so there are no aspects without a timestamp.
+//! Note that ties compute the addresses on every comparison.
+
+use crate::network::entry_aspect::EntryAspect;
+use holochain_persistence_api::cas::content::AddressableContent;
+use std::{cmp::Ordering, ops::Deref};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByTime(pub EntryAspect);
+
+impl ByTime {
+    pub fn into_aspect(self) -> EntryAspect {
+        self.0
+    }
+}
+
+impl From<EntryAspect> for ByTime {
+    fn from(aspect: EntryAspect) -> Self {
+        ByTime(aspect)
+    }
+}
+
+impl Deref for ByTime {
+    type Target = EntryAspect;
+
+    fn deref(&self) -> &EntryAspect {
+        &self.0
+    }
+}
+
+impl Ord for ByTime {
+    fn cmp(&self, other: &ByTime) -> Ordering {
+        self.header()
+            .timestamp()
+            .cmp(other.header().timestamp())
+            .then_with(|| self.address().cmp(&other.address()))
+    }
+}
+
+impl PartialOrd for ByTime {
+    fn partial_cmp(&self, other: &ByTime) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        entry::{test_entry, test_entry_b, test_entry_c, Entry},
+        network::entry_aspect::tests::test_header_for,
+        time::Iso8601,
+    };
+
+    #[test]
+    fn chronological_order_test() {
+        fn at(entry: Entry, secs: i64) -> ByTime {
+            let header = test_header_for(&entry, &Iso8601::from(secs));
+            ByTime(EntryAspect::Content(entry, header))
+        }
+        let first = at(test_entry_b(), 10);
+        let second = at(test_entry_c(), 20);
+        let third = at(test_entry(), 30);
+
+        let mut aspects = vec![third.clone(), first.clone(), second.clone()];
+        aspects.sort();
+        assert_eq!(aspects, vec![first.clone(), second, third]);
+
+        // same timestamp: by address
+        let twin = at(test_entry(), 10);
+        let mut by_address = vec![first.address(), twin.address()];
+        by_address.sort();
+        let mut twins = vec![twin, first];
+        twins.sort();
+        let sorted: Vec<_> = twins.iter().map(|aspect| aspect.address()).collect();
+        assert_eq!(sorted, by_address);
+    }
+}
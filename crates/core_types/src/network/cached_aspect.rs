@@ -0,0 +1,178 @@
+//! CachedAspect wraps an EntryAspect together with its memoized address
+//! (and header address), so that aspects of big entries don't get serialized
+//! and hashed again every time their address is needed. When both sides of a comparison hold their
+//! address, equality is decided on the addresses alone.
+
+use crate::network::entry_aspect::EntryAspect;
+use holochain_json_api::error::JsonError;
+use holochain_persistence_api::cas::content::{Address, AddressableContent, Content};
+use std::hash::{Hash, Hasher};
+
+#[derive(Clone, Debug)]
+pub struct CachedAspect {
+    aspect: EntryAspect,
+    address: Option<Address>,
+    header_address: Option<Address>,
+}
+
+impl CachedAspect {
+    /// Wraps the aspect without computing its address yet.
+    pub fn new(aspect: EntryAspect) -> Self {
+        CachedAspect {
+            aspect,
+            address: None,
+            header_address: None,
+        }
+    }
+
+    /// Wraps the aspect and computes its address and header address right away.
+    pub fn with_address(aspect: EntryAspect) -> Self {
+        let address = Some(aspect.address());
+        let header_address = Some(aspect.header_address());
+        CachedAspect {
+            aspect,
+            address,
+            header_address,
+        }
+    }
+
+    pub fn aspect(&self) -> &EntryAspect {
+        &self.aspect
+    }
+
+    pub fn into_aspect(self) -> EntryAspect {
+        self.aspect
+    }
+
+    /// The memoized address, if it got computed already.
+    pub fn cached_address(&self) -> Option<&Address> {
+        self.address.as_ref()
+    }
+
+    /// Computes the address if needed and keeps it for later calls.
+    pub fn memoize_address(&mut self) -> &Address {
+        if self.address.is_none() {
+            self.address = Some(self.aspect.address());
+        }
+        self.address.as_ref().unwrap()
+    }
+
+    /// Same as memoize_address() for the header address.
+    /// The header can't change once it is in the aspect, so it is computed at most once.
+    pub fn memoize_header_address(&mut self) -> &Address {
+        if self.header_address.is_none() {
+            self.header_address = Some(self.aspect.header_address());
+        }
+        self.header_address.as_ref().unwrap()
+    }
+}
+
+impl From<EntryAspect> for CachedAspect {
+    fn from(aspect: EntryAspect) -> Self {
+        CachedAspect::new(aspect)
+    }
+}
+
+impl AddressableContent for CachedAspect {
+    fn address(&self) -> Address {
+        self.address
+            .clone()
+            .unwrap_or_else(|| self.aspect.address())
+    }
+
+    fn content(&self) -> Content {
+        self.aspect.content()
+    }
+
+    fn try_from_content(content: &Content) -> Result<Self, JsonError> {
+        EntryAspect::try_from_content(content).map(CachedAspect::new)
+    }
+}
+
+/// Aspects are content addressed, so equal addresses mean equal aspects.
+/// Only if one of the addresses is not cached the aspects get compared field by field.
+impl PartialEq for CachedAspect {
+    fn eq(&self, other: &CachedAspect) -> bool {
+        match (&self.address, &other.address) {
+            (Some(address), Some(other_address)) => address == other_address,
+            _ => self.aspect == other.aspect,
+        }
+    }
+}
+
+impl Eq for CachedAspect {}
+
+impl Hash for CachedAspect {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.aspect.hash(state)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::network::{
+        aspect_store::tests::test_aspects, entry_aspect::tests::test_content_aspect,
+    };
+
+    #[test]
+    fn memoize_address_test() {
+        let mut cached = CachedAspect::new(test_content_aspect());
+        assert_eq!(cached.cached_address(), None);
+        assert_eq!(cached.address(), test_content_aspect().address());
+        assert_eq!(cached.memoize_address(), &test_content_aspect().address());
+        assert_eq!(
+            cached.cached_address(),
+            Some(&test_content_aspect().address())
+        );
+    }
+
+    #[test]
+    fn memoize_header_address_test() {
+        let mut cached = CachedAspect::new(test_content_aspect());
+        assert_eq!(
+            cached.memoize_header_address(),
+            &test_content_aspect().header_address()
+        );
+        assert_eq!(
+            CachedAspect::with_address(test_content_aspect()).header_address,
+            Some(test_content_aspect().header_address())
+        );
+    }
+
+    #[test]
+    fn eq_matches_entry_aspect_eq_test() {
+        for a in test_aspects() {
+            for b in test_aspects() {
+                let expected = a == b;
+                let uncached = CachedAspect::new(a.clone()) == CachedAspect::new(b.clone());
+                let cached =
+                    CachedAspect::with_address(a.clone()) == CachedAspect::with_address(b.clone());
+                let mixed = CachedAspect::with_address(a.clone()) == CachedAspect::new(b.clone());
+                assert_eq!(uncached, expected);
+                assert_eq!(cached, expected);
+                assert_eq!(mixed, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn eq_short_circuits_on_cached_addresses_test() {
+        // a (deliberately wrong) cached address shows that the aspects themselves
+        // are not looked at when both addresses are present
+        let aspects = test_aspects();
+        let address = aspects[0].address();
+        let a = CachedAspect {
+            aspect: aspects[0].clone(),
+            address: Some(address.clone()),
+            header_address: None,
+        };
+        let b = CachedAspect {
+            aspect: aspects[1].clone(),
+            address: Some(address),
+            header_address: None,
+        };
+        assert_eq!(a, b);
+        assert_ne!(a, CachedAspect::new(aspects[1].clone()));
+    }
+}
@@ -0,0 +1,175 @@
+//! CompactAspect is an alternative wire schema for EntryAspects that saves
+//! bandwidth: instead of the variant name, an aspect is serialized as a
+//! two element tuple of a small integer tag and the variant's payload,
+//! e.g. `[2,[<link data>,<header>]]` for a `LinkAdd`.
+//!
+//! This schema is for transport only. Aspects are content addressed by their
+//! regular JSON form (EntryAspect::content()), so a CompactAspect has no
+//! address of its own: convert it back into an EntryAspect before addressing,
+//! storing or hashing it.
+
+use crate::{
+    chain_header::ChainHeader,
+    entry::Entry,
+    link::link_data::LinkData,
+    network::entry_aspect::{AspectType, EntryAspect},
+};
+use holochain_persistence_api::cas::content::Address;
+use serde::{
+    de::{self, Deserialize, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, SerializeTuple, Serializer},
+};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactAspect(pub EntryAspect);
+
+/// The integer tag of each aspect type in the compact schema.
+/// These are part of the wire format and must never change.
+fn compact_tag(aspect_type: AspectType) -> u8 {
+    match aspect_type {
+        AspectType::Content => 0,
+        AspectType::Header => 1,
+        AspectType::LinkAdd => 2,
+        AspectType::LinkRemove => 3,
+        AspectType::Update => 4,
+        AspectType::Deletion => 5,
+    }
+}
+
+impl From<EntryAspect> for CompactAspect {
+    fn from(aspect: EntryAspect) -> Self {
+        CompactAspect(aspect)
+    }
+}
+
+impl From<CompactAspect> for EntryAspect {
+    fn from(compact: CompactAspect) -> Self {
+        compact.0
+    }
+}
+
+impl Serialize for CompactAspect {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&compact_tag(self.0.aspect_type()))?;
+        match &self.0 {
+            EntryAspect::Content(entry, header) | EntryAspect::Update(entry, header) => {
+                tuple.serialize_element(&(entry, header))?
+            }
+            EntryAspect::Header(header) | EntryAspect::Deletion(header) => {
+                tuple.serialize_element(header)?
+            }
+            EntryAspect::LinkAdd(link_data, header) => {
+                tuple.serialize_element(&(link_data, header))?
+            }
+            EntryAspect::LinkRemove(removal, header) => {
+                tuple.serialize_element(&(removal, header))?
+            }
+        }
+        tuple.end()
+    }
+}
+
+/// Reads the payload, i.e. the second element of the tuple.
+fn next_payload<'de, T, A>(seq: &mut A) -> Result<T, A::Error>
+where
+    T: Deserialize<'de>,
+    A: SeqAccess<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| de::Error::invalid_length(1, &"a tag and a payload"))
+}
+
+struct CompactAspectVisitor;
+
+impl<'de> Visitor<'de> for CompactAspectVisitor {
+    type Value = CompactAspect;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tag and a payload")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<CompactAspect, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let tag: u8 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let aspect = match tag {
+            0 => {
+                let (entry, header): (Entry, ChainHeader) = next_payload(&mut seq)?;
+                EntryAspect::Content(entry, header)
+            }
+            1 => EntryAspect::Header(next_payload(&mut seq)?),
+            2 => {
+                let (link_data, header): (LinkData, ChainHeader) = next_payload(&mut seq)?;
+                EntryAspect::LinkAdd(link_data, header)
+            }
+            3 => {
+                let (removal, header): ((LinkData, Vec<Address>), ChainHeader) =
+                    next_payload(&mut seq)?;
+                EntryAspect::LinkRemove(removal, header)
+            }
+            4 => {
+                let (entry, header): (Entry, ChainHeader) = next_payload(&mut seq)?;
+                EntryAspect::Update(entry, header)
+            }
+            5 => EntryAspect::Deletion(next_payload(&mut seq)?),
+            other => {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(u64::from(other)),
+                    &"an aspect tag from 0 to 5",
+                ))
+            }
+        };
+        Ok(CompactAspect(aspect))
+    }
+}
+
+impl<'de> Deserialize<'de> for CompactAspect {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, CompactAspectVisitor)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::network::{
+        aspect_store::tests::test_aspects, entry_aspect::tests::test_content_aspect,
+    };
+    use holochain_persistence_api::cas::content::AddressableContent;
+    use serde_json;
+
+    #[test]
+    fn compact_round_trip_test() {
+        for aspect in test_aspects() {
+            let json = serde_json::to_string(&CompactAspect::from(aspect.clone())).unwrap();
+            assert!(json.len() < String::from(aspect.content()).len());
+            let compact: CompactAspect = serde_json::from_str(&json).unwrap();
+            assert_eq!(EntryAspect::from(compact), aspect);
+        }
+    }
+
+    #[test]
+    fn compact_schema_test() {
+        let json = serde_json::to_string(&CompactAspect(test_content_aspect())).unwrap();
+        assert!(json.starts_with("[0,"));
+        assert!(!json.contains("Content"));
+
+        // the regular form is not accepted, and neither are unknown tags
+        assert!(serde_json::from_str::<CompactAspect>(&String::from(
+            test_content_aspect().content()
+        ))
+        .is_err());
+        assert!(serde_json::from_str::<CompactAspect>(&json.replacen("0", "9", 1)).is_err());
+    }
+}
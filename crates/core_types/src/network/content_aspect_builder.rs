@@ -0,0 +1,161 @@
+//! ContentAspectBuilder assembles a `Content` aspect together with a consistent
+//! header for its entry: entry type and address are taken from the entry, the
+//! chain links from the previous header.
+//! This is meant for tools and tests that construct chains; the headers of a
+//! running agent come from its source chain.
+
+use crate::{
+    chain_header::ChainHeader,
+    entry::Entry,
+    error::HolochainError,
+    network::entry_aspect::EntryAspect,
+    signature::{Provenance, Signature},
+    time::Iso8601,
+};
+use holochain_persistence_api::cas::content::{Address, AddressableContent};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentAspectBuilder {
+    entry: Entry,
+    prev: Option<ChainHeader>,
+    prev_same_type: Option<Option<Address>>,
+    provenances: Vec<Provenance>,
+    timestamp: Option<Iso8601>,
+}
+
+impl ContentAspectBuilder {
+    pub fn new(entry: Entry) -> Self {
+        ContentAspectBuilder {
+            entry,
+            prev: None,
+            prev_same_type: None,
+            provenances: Vec::new(),
+            timestamp: None,
+        }
+    }
+
+    /// The header this entry follows on the chain. Without one the header is
+    /// built as the first of its chain.
+    pub fn prev(mut self, prev: ChainHeader) -> Self {
+        self.prev = Some(prev);
+        self
+    }
+
+    /// The header of the most recent entry of the same type, if that is not
+    /// prev() itself. By default this is prev() if it has the same entry type,
+    /// None otherwise.
+    pub fn prev_same_type(mut self, prev_same_type: Option<Address>) -> Self {
+        self.prev_same_type = Some(prev_same_type);
+        self
+    }
+
+    /// Adds `agent` as an author with a fake signature, as nothing here can sign.
+    /// Use provenance() to add an actual signature.
+    pub fn author(self, agent: Address) -> Self {
+        self.provenance(Provenance::new(agent, Signature::fake()))
+    }
+
+    pub fn provenance(mut self, provenance: Provenance) -> Self {
+        self.provenances.push(provenance);
+        self
+    }
+
+    /// Defaults to the timestamp of prev(), or the Unix epoch for the first header.
+    pub fn timestamp(mut self, timestamp: Iso8601) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// The header for the entry, as it goes into the built aspect.
+    pub fn header(&self) -> ChainHeader {
+        let entry_type = self.entry.entry_type();
+        let link = self.prev.as_ref().map(AddressableContent::address);
+        let link_same_type = match &self.prev_same_type {
+            Some(prev_same_type) => prev_same_type.clone(),
+            None => self
+                .prev
+                .as_ref()
+                .filter(|prev| *prev.entry_type() == entry_type)
+                .map(AddressableContent::address),
+        };
+        let timestamp = match (&self.timestamp, &self.prev) {
+            (Some(timestamp), _) => *timestamp,
+            (None, Some(prev)) => *prev.timestamp(),
+            (None, None) => Iso8601::from(0),
+        };
+        ChainHeader::new(
+            &entry_type,
+            &self.entry.address(),
+            &self.provenances,
+            &link,
+            &link_same_type,
+            &None,
+            &timestamp,
+        )
+    }
+
+    /// The `Content` aspect of the entry with header(). Fails if that aspect is
+    /// not self-consistent (see EntryAspect::validate_self_consistency()).
+    pub fn build(self) -> Result<EntryAspect, HolochainError> {
+        let header = self.header();
+        let aspect = EntryAspect::Content(self.entry, header);
+        aspect.validate_self_consistency(false)?;
+        Ok(aspect)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::test_chain_header,
+        entry::{entry_type::test_app_entry_type, test_entry, test_entry_b, test_entry_value_b},
+        link::link_data::example_link_add,
+        time::test_iso_8601,
+    };
+
+    #[test]
+    fn build_test() {
+        // of the same type as the entry of prev
+        let entry = Entry::App(test_app_entry_type(), test_entry_value_b());
+        let prev = test_chain_header();
+        let aspect = ContentAspectBuilder::new(entry.clone())
+            .prev(prev.clone())
+            .author(test_agent_id().address())
+            .build()
+            .unwrap();
+
+        assert!(aspect.validate_self_consistency(false).is_ok());
+        let header = aspect.header();
+        assert_eq!(header.link(), Some(prev.address()));
+        assert_eq!(header.link_same_type(), Some(prev.address()));
+        assert_eq!(*header.entry_address(), entry.address());
+        assert_eq!(header.entry_type(), &entry.entry_type());
+        assert_eq!(*header.timestamp(), test_iso_8601());
+        assert_eq!(aspect.author(), Some(test_agent_id().address()));
+
+        // an entry of another type has no predecessor of its type
+        let header = ContentAspectBuilder::new(test_entry_b())
+            .prev(prev.clone())
+            .header();
+        assert_eq!(header.link(), Some(prev.address()));
+        assert_eq!(header.link_same_type(), None);
+        assert_eq!(header.entry_type(), &test_entry_b().entry_type());
+    }
+
+    #[test]
+    fn first_header_test() {
+        let header = ContentAspectBuilder::new(test_entry()).header();
+        assert_eq!(header.link(), None);
+        assert_eq!(header.link_same_type(), None);
+        assert!(header.provenances().is_empty());
+
+        // a link entry following an app entry has no predecessor of its type
+        let header = ContentAspectBuilder::new(Entry::LinkAdd(example_link_add()))
+            .prev(test_chain_header())
+            .header();
+        assert_eq!(header.link(), Some(test_chain_header().address()));
+        assert_eq!(header.link_same_type(), None);
+    }
+}
@@ -0,0 +1,93 @@
+//! DuplicateGuard suppresses aspects that are gossiped again shortly after
+//! they were seen, to defend against flooding.
+//! Unlike AspectCache it does not keep the aspects, only the addresses of the
+//! last N observations, so its memory use is capped by the window size.
+
+use crate::network::entry_aspect::EntryAspect;
+use holochain_persistence_api::cas::content::{Address, AddressableContent};
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Clone, Debug)]
+pub struct DuplicateGuard {
+    window: usize,
+    /// Addresses of the last `window` observations, oldest first
+    recent: VecDeque<Address>,
+    /// How often each address occurs in `recent`
+    counts: HashMap<Address, usize>,
+}
+
+impl DuplicateGuard {
+    /// A guard that remembers the last `window` observations.
+    pub fn new(window: usize) -> Self {
+        DuplicateGuard {
+            window,
+            recent: VecDeque::with_capacity(window),
+            counts: HashMap::new(),
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Records an observation of `aspect` and returns true if its address was
+    /// observed within the last `window` observations already.
+    pub fn seen(&mut self, aspect: &EntryAspect) -> bool {
+        self.seen_address(aspect.address())
+    }
+
+    /// Same as seen() for an aspect address.
+    pub fn seen_address(&mut self, address: Address) -> bool {
+        let seen = self.counts.contains_key(&address);
+        if self.window == 0 {
+            return seen;
+        }
+        if self.recent.len() == self.window {
+            if let Some(oldest) = self.recent.pop_front() {
+                let remaining = {
+                    let count = self
+                        .counts
+                        .get_mut(&oldest)
+                        .expect("every recent address is counted");
+                    *count -= 1;
+                    *count
+                };
+                if remaining == 0 {
+                    self.counts.remove(&oldest);
+                }
+            }
+        }
+        *self.counts.entry(address.clone()).or_insert(0) += 1;
+        self.recent.push_back(address);
+        seen
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::network::entry_aspect::tests::{
+        test_content_aspect, test_deletion_aspect, test_link_add_aspect,
+    };
+
+    #[test]
+    fn repeat_within_window_test() {
+        let mut guard = DuplicateGuard::new(2);
+        assert!(!guard.seen(&test_content_aspect()));
+        assert!(guard.seen(&test_content_aspect()));
+        assert!(!guard.seen(&test_link_add_aspect()));
+        assert!(guard.seen(&test_content_aspect()));
+    }
+
+    #[test]
+    fn repeat_after_eviction_test() {
+        let mut guard = DuplicateGuard::new(2);
+        assert!(!guard.seen(&test_content_aspect()));
+        assert!(!guard.seen(&test_link_add_aspect()));
+        assert!(!guard.seen(&test_deletion_aspect()));
+        // the content aspect dropped out of the window
+        assert!(!guard.seen(&test_content_aspect()));
+        assert_eq!(guard.recent.len(), 2);
+        assert_eq!(guard.counts.len(), 2);
+    }
+}
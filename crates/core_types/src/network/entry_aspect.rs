@@ -1,24 +1,96 @@
 use crate::{
-    chain_header::ChainHeader, entry::Entry, error::HolochainError, link::link_data::LinkData,
+    chain_header::ChainHeader,
+    eav::{self, Attribute, EntityAttributeValueIndex},
+    entry::{entry_type::EntryType, Entry},
+    error::HolochainError,
+    link::{link_data::LinkData, Link},
+    network::aspect_store::AspectStore,
+    signature::Provenance,
+    time::{Clock, Iso8601},
 };
+use chrono::{offset::FixedOffset, DateTime};
 use holochain_json_api::{error::JsonError, json::JsonString};
 use holochain_persistence_api::cas::content::{Address, AddressableContent, Content};
+use multihash::{self, Hash as HashAlgorithm};
+use serde::Serialize;
+use serde_json;
 use std::{
+    collections::{BTreeMap, HashMap},
     convert::{Into, TryFrom},
     fmt,
     hash::{Hash, Hasher},
+    str::FromStr,
+    time::Duration,
 };
 
+/// Default cap on the serialized size of a single aspect, for use with
+/// `EntryAspect::to_content_capped`. Aspects above this are refused to
+/// protect nodes from memory-bomb payloads.
+pub const MAX_ASPECT_BYTES: usize = 16 * 1024 * 1024;
+
 impl AddressableContent for EntryAspect {
+    /// The canonical_bytes() as JSON.
+    /// The address is the default: the SHA2-256 multihash of exactly these bytes.
     fn content(&self) -> Content {
-        self.to_owned().into()
+        let bytes = self
+            .canonical_bytes()
+            .expect("could not serialize entry aspect");
+        JsonString::from_json(&String::from_utf8(bytes).expect("JSON is valid UTF-8"))
     }
 
+    /// Strict decode for data that ends up in a store: on top of the JSON decode
+    /// this rejects aspects no honest node would produce, i.e. a `LinkRemove` that
+    /// removes no link. Use `EntryAspect::try_from(JsonString)` to inspect such data.
     fn try_from_content(content: &Content) -> Result<Self, JsonError> {
-        Self::try_from(content.to_owned())
+        let aspect = Self::try_from(content.to_owned())?;
+        if let EntryAspect::LinkRemove((_, removed), _) = &aspect {
+            if removed.is_empty() {
+                return Err(JsonError::SerializationError(String::from(
+                    "link_remove aspect does not remove any link address",
+                )));
+            }
+        }
+        Ok(aspect)
+    }
+}
+
+/// Decodes an aspect from already parsed JSON, e.g. a request body.
+/// Same result as `EntryAspect::try_from(JsonString)` on the serialized value, without
+/// serializing it first. Like that (and unlike try_from_content()) it is not strict.
+impl TryFrom<serde_json::Value> for EntryAspect {
+    type Error = HolochainError;
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Decodes an aspect from JSON text, e.g. read from a file or a request body.
+/// Same as `EntryAspect::try_from_content()` on the text as Content, so strict.
+impl TryFrom<&str> for EntryAspect {
+    type Error = HolochainError;
+    fn try_from(json: &str) -> Result<Self, Self::Error> {
+        Ok(EntryAspect::try_from_content(&JsonString::from_json(json))?)
     }
 }
 
+/// The pieces of data that get gossiped and held in the DHT for an entry.
+///
+/// The variants can be constructed directly for compatibility, but that is
+/// unchecked: nothing guarantees that e.g. the header is the header of the entry.
+/// New code should use the checked constructors (checked_content(),
+/// checked_update(), checked_deletion()) or the builders (ContentAspectBuilder,
+/// LinkRemoveAspectBuilder), which only return self-consistent aspects:
+///
+/// ```
+/// use holochain_core_types::{
+///     chain_header::test_chain_header, entry::{test_entry, test_entry_b},
+///     network::entry_aspect::EntryAspect,
+/// };
+///
+/// assert!(EntryAspect::checked_content(test_entry(), test_chain_header()).is_ok());
+/// // test_chain_header() is the header of test_entry()
+/// assert!(EntryAspect::checked_content(test_entry_b(), test_chain_header()).is_err());
+/// ```
 #[derive(Serialize, Deserialize, PartialEq, Eq, DefaultJson, Clone)]
 #[allow(clippy::large_enum_variant)]
 pub enum EntryAspect {
@@ -74,15 +146,178 @@ pub enum EntryAspect {
     Deletion(ChainHeader),
 }
 
+/// The variant of an EntryAspect, without its data.
+/// Displays as (and parses from) the aspect's type_hint().
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AspectType {
+    Content,
+    Header,
+    LinkAdd,
+    LinkRemove,
+    Update,
+    Deletion,
+}
+
+impl AspectType {
+    /// All aspect types, in the order of the EntryAspect variants
+    pub const ALL: [AspectType; 6] = [
+        AspectType::Content,
+        AspectType::Header,
+        AspectType::LinkAdd,
+        AspectType::LinkRemove,
+        AspectType::Update,
+        AspectType::Deletion,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            AspectType::Content => "content",
+            AspectType::Header => "header",
+            AspectType::LinkAdd => "link_add",
+            AspectType::LinkRemove => "link_remove",
+            AspectType::Update => "update",
+            AspectType::Deletion => "deletion",
+        }
+    }
+}
+
+impl fmt::Display for AspectType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for AspectType {
+    type Err = HolochainError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AspectType::ALL
+            .iter()
+            .find(|aspect_type| aspect_type.as_str() == s)
+            .cloned()
+            .ok_or_else(|| HolochainError::ErrorGeneric(format!("Unknown aspect type: {}", s)))
+    }
+}
+
+/// How forgiving EntryAspect::decode() is, from strictest to most lenient.
+/// Every mode accepts everything the stricter modes accept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DecodeMode {
+    /// Only well-formed aspects, see AddressableContent::try_from_content()
+    Strict,
+    /// Also aspects that are merely unusual (e.g. a `LinkRemove` that removes nothing),
+    /// and `Ok(None)` for variants this version does not know
+    TolerateUnknown,
+    /// Also the legacy `{"Deletion": [<deleted address>, <header>]}` form
+    RepairLegacy,
+    /// Also untagged aspects, see EntryAspect::from_content_structural()
+    InferStructural,
+}
+
+/// The serialized names of the EntryAspect variants
+const VARIANT_NAMES: [&str; 6] = [
+    "Content",
+    "Header",
+    "LinkAdd",
+    "LinkRemove",
+    "Update",
+    "Deletion",
+];
+
+/// Sort key for replaying aspects of several agents in a deterministic order,
+/// see EntryAspect::replay_key().
+///
+/// Headers don't record their position in the chain, only the previous header's
+/// address, so a chain position can't be derived from a single aspect. What the
+/// key can tell is whether the header starts a chain (`0`) or not (`1`).
+/// Aspects are therefore ordered by:
+/// 1. timestamp: across agents this is the best order available, and within an
+///    agent's chain it is chain order as long as the agent's clock did not go back
+/// 2. author, so that aspects of one agent with the same timestamp stay together
+/// 3. the best-effort chain position, so that a chain's first header goes first
+/// 4. header address, to make the order total
+///
+/// Aspects with the same header (e.g. a `Content` and its meta aspect) get the
+/// same key.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReplayKey {
+    timestamp: Iso8601,
+    author: Option<Address>,
+    chain_position: u8,
+    header_address: Address,
+}
+
 impl EntryAspect {
+    /// A `Content` aspect, checked by validate_self_consistency().
+    pub fn checked_content(
+        entry: Entry,
+        header: ChainHeader,
+    ) -> Result<EntryAspect, HolochainError> {
+        EntryAspect::Content(entry, header).checked()
+    }
+
+    /// An `Update` aspect, checked by validate_self_consistency().
+    pub fn checked_update(
+        entry: Entry,
+        header: ChainHeader,
+    ) -> Result<EntryAspect, HolochainError> {
+        EntryAspect::Update(entry, header).checked()
+    }
+
+    /// A `Deletion` aspect, checked by validate_self_consistency().
+    pub fn checked_deletion(header: ChainHeader) -> Result<EntryAspect, HolochainError> {
+        EntryAspect::Deletion(header).checked()
+    }
+
+    fn checked(self) -> Result<EntryAspect, HolochainError> {
+        self.validate_self_consistency(false)?;
+        Ok(self)
+    }
+
+    /// The bytes hashed to compute address(), i.e. the compact JSON serialization of
+    /// the aspect. Tools that want to reproduce an aspect's address can hash these
+    /// with SHA2-256 and encode the multihash in base58.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>, HolochainError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn aspect_type(&self) -> AspectType {
+        match self {
+            EntryAspect::Content(_, _) => AspectType::Content,
+            EntryAspect::Header(_) => AspectType::Header,
+            EntryAspect::LinkAdd(_, _) => AspectType::LinkAdd,
+            EntryAspect::LinkRemove(_, _) => AspectType::LinkRemove,
+            EntryAspect::Update(_, _) => AspectType::Update,
+            EntryAspect::Deletion(_) => AspectType::Deletion,
+        }
+    }
     pub fn type_hint(&self) -> String {
+        self.aspect_type().to_string()
+    }
+
+    /// Whether validating this aspect needs the header and its source.
+    /// Every variant carries a header and none can be validated without it,
+    /// so this is always true (see the comments on the variants).
+    pub fn requires_source_for_validation(&self) -> bool {
+        true
+    }
+
+    /// Whether this aspect ships an entry or link payload next to its header:
+    ///
+    /// | variant      | payload             |
+    /// |--------------|---------------------|
+    /// | `Content`    | yes, the entry      |
+    /// | `Header`     | no (tombstone)      |
+    /// | `LinkAdd`    | yes, the link data  |
+    /// | `LinkRemove` | yes, the link data  |
+    /// | `Update`     | yes, the new entry  |
+    /// | `Deletion`   | no                  |
+    pub fn carries_payload(&self) -> bool {
         match self {
-            EntryAspect::Content(_, _) => String::from("content"),
-            EntryAspect::Header(_) => String::from("header"),
-            EntryAspect::LinkAdd(_, _) => String::from("link_add"),
-            EntryAspect::LinkRemove(_, _) => String::from("link_remove"),
-            EntryAspect::Update(_, _) => String::from("update"),
-            EntryAspect::Deletion(_) => String::from("deletion"),
+            EntryAspect::Content(_, _)
+            | EntryAspect::LinkAdd(_, _)
+            | EntryAspect::LinkRemove(_, _)
+            | EntryAspect::Update(_, _) => true,
+            EntryAspect::Header(_) | EntryAspect::Deletion(_) => false,
         }
     }
     pub fn header(&self) -> &ChainHeader {
@@ -95,6 +330,105 @@ impl EntryAspect {
             EntryAspect::Deletion(header) => header,
         }
     }
+
+    /// The link data of `LinkAdd` and `LinkRemove` aspects
+    pub fn link_data(&self) -> Option<&LinkData> {
+        match self {
+            EntryAspect::LinkAdd(link_data, _) | EntryAspect::LinkRemove((link_data, _), _) => {
+                Some(link_data)
+            }
+            _ => None,
+        }
+    }
+
+    /// The link of `LinkAdd` and `LinkRemove` aspects
+    pub fn as_link(&self) -> Option<&Link> {
+        self.link_data().map(LinkData::link)
+    }
+
+    /// The tag of the link, for link aspects
+    pub fn link_tag(&self) -> Option<&str> {
+        self.as_link().map(|link| link.tag().as_str())
+    }
+
+    /// The type of the link, for link aspects
+    pub fn link_type(&self) -> Option<&str> {
+        self.as_link().map(|link| link.link_type().as_str())
+    }
+
+    /// Consumes the aspect into its owned parts, so that e.g. a big entry can be
+    /// moved out without cloning: (type, header, entry of `Content` and `Update`,
+    /// link data of `LinkAdd` and `LinkRemove`, addresses removed by a `LinkRemove`).
+    /// Parts a variant doesn't have are None or empty.
+    pub fn into_parts(
+        self,
+    ) -> (
+        AspectType,
+        ChainHeader,
+        Option<Entry>,
+        Option<LinkData>,
+        Vec<Address>,
+    ) {
+        let aspect_type = self.aspect_type();
+        match self {
+            EntryAspect::Content(entry, header) | EntryAspect::Update(entry, header) => {
+                (aspect_type, header, Some(entry), None, Vec::new())
+            }
+            EntryAspect::LinkAdd(link_data, header) => {
+                (aspect_type, header, None, Some(link_data), Vec::new())
+            }
+            EntryAspect::LinkRemove((link_data, removed), header) => {
+                (aspect_type, header, None, Some(link_data), removed)
+            }
+            EntryAspect::Header(header) | EntryAspect::Deletion(header) => {
+                (aspect_type, header, None, None, Vec::new())
+            }
+        }
+    }
+
+    /// The address of the header preceding this aspect's header in its author's
+    /// source chain, None for the first header of a chain.
+    pub fn previous_header_address(&self) -> Option<Address> {
+        self.header().link()
+    }
+
+    /// The address of the aspect's header. The header is part of the aspect's
+    /// identity: two aspects with the same payload but different headers are
+    /// different aspects (and have different addresses).
+    /// This is computed on every call, see CachedAspect for a memoized version.
+    pub fn header_address(&self) -> Address {
+        self.header().address()
+    }
+
+    /// All provenances of the header: the author's first (see author()), then those
+    /// of any further signers.
+    pub fn provenances(&self) -> &[Provenance] {
+        self.header().provenances()
+    }
+
+    /// The agent that authored the aspect, i.e. the source of the header's first
+    /// provenance. None if the header has no provenances.
+    pub fn author(&self) -> Option<Address> {
+        self.header()
+            .provenances()
+            .first()
+            .map(|provenance| provenance.source())
+    }
+
+    /// True if `agent` is the author(), false if it is not or if there is no author.
+    pub fn author_matches(&self, agent: &Address) -> bool {
+        self.author().as_ref() == Some(agent)
+    }
+
+    /// True if both aspects were authored by the same agent, i.e. come from the same
+    /// source chain. False if the author() of either of them is unknown.
+    pub fn same_chain(&self, other: &EntryAspect) -> bool {
+        match self.author() {
+            Some(author) => other.author_matches(&author),
+            None => false,
+        }
+    }
+
     /// NB: this is the inverse function of entry_to_meta_aspect,
     /// so it is very important that they agree!
     /// NOTE: the ContentAspect address is always the entry address and this
@@ -106,7 +440,7 @@ impl EntryAspect {
             EntryAspect::LinkRemove((link_data, _), _) => link_data.link.base().clone(),
             EntryAspect::Update(_, header) | EntryAspect::Deletion(header) => {
                 header.link_update_delete().ok_or_else(|| {
-                    HolochainError::ErrorGeneric(format!(
+                    HolochainError::MissingCrudLink(format!(
                         "no link_update_delete on Update/Deletion entry header. Header: {:?}",
                         header
                     ))
@@ -117,13 +451,949 @@ impl EntryAspect {
             EntryAspect::Header(header) => header.address(),
         })
     }
+
+    /// What this aspect attaches to: the link base of `LinkAdd` and `LinkRemove`, and
+    /// the entry that `Update` and `Deletion` replace or delete (their header's CRUD
+    /// link, None if it is missing). None for `Content` and `Header`, which attach to
+    /// nothing but stand for themselves.
+    ///
+    /// For the meta variants this is the entry_address() the aspect is stored at;
+    /// entry_address() in addition gives an address for `Content` (its entry) and
+    /// `Header` (the header itself), where the aspect is stored as well.
+    pub fn base_address(&self) -> Option<Address> {
+        match self {
+            EntryAspect::LinkAdd(link_data, _) | EntryAspect::LinkRemove((link_data, _), _) => {
+                Some(link_data.link.base().clone())
+            }
+            EntryAspect::Update(_, header) | EntryAspect::Deletion(header) => {
+                header.link_update_delete()
+            }
+            EntryAspect::Content(_, _) | EntryAspect::Header(_) => None,
+        }
+    }
+
+    /// The DHT (EAV) attribute under which a meta aspect gets stored at its
+    /// entry_address(), rendered as string:
+    /// * `LinkAdd`: `link__<link type>__<tag>` (Attribute::LinkTag)
+    /// * `LinkRemove`: `removed_link__<link remove entry address>__<link type>__<tag>`
+    ///   (Attribute::RemovedLink)
+    /// * `Update`: `crud-link`, it links the base to its new version
+    /// * `Deletion`: `crud-status`, it marks the base as deleted
+    ///
+    /// None for `Content` and `Header`, which are not meta.
+    /// Errors for `Update` and `Deletion` aspects without a CRUD link.
+    pub fn meta_attribute(&self) -> Result<Option<String>, HolochainError> {
+        Ok(self.eav_attribute()?.map(|attribute| attribute.to_string()))
+    }
+
+    /// The EAV triple that records this meta aspect: its entry_address() as entity,
+    /// the meta_attribute() as attribute and the aspect's own address as value.
+    /// None for `Content` and `Header`, which are not meta aspects.
+    pub fn to_eav(&self) -> Result<Option<EntityAttributeValueIndex>, HolochainError> {
+        match self.eav_attribute()? {
+            Some(attribute) => Ok(Some(eav::new(
+                &self.entry_address()?,
+                &attribute,
+                &self.address(),
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    /// meta_attribute() as an Attribute
+    fn eav_attribute(&self) -> Result<Option<Attribute>, HolochainError> {
+        let attribute = match self {
+            EntryAspect::Content(_, _) | EntryAspect::Header(_) => return Ok(None),
+            EntryAspect::LinkAdd(link_data, _) => Attribute::LinkTag(
+                link_data.link.link_type().to_string(),
+                link_data.link.tag().to_string(),
+            ),
+            EntryAspect::LinkRemove((link_data, _), header) => Attribute::RemovedLink(
+                header.entry_address().clone(),
+                link_data.link.link_type().to_string(),
+                link_data.link.tag().to_string(),
+            ),
+            EntryAspect::Update(_, _) => {
+                self.entry_address()?;
+                Attribute::CrudLink
+            }
+            EntryAspect::Deletion(_) => {
+                self.entry_address()?;
+                Attribute::CrudStatus
+            }
+        };
+        Ok(Some(attribute))
+    }
+
+    /// The address of the entry a `Deletion` aspect deletes.
+    /// The header's CRUD link takes precedence. Only if the header has none,
+    /// `fallback` is used, e.g. the address legacy deletion aspects stored next to
+    /// the header. Errors if neither is there or if this is not a `Deletion` aspect.
+    pub fn deleted_address(&self, fallback: Option<&Address>) -> Result<Address, HolochainError> {
+        match self {
+            EntryAspect::Deletion(header) => header
+                .link_update_delete()
+                .or_else(|| fallback.cloned())
+                .ok_or_else(|| {
+                    HolochainError::MissingCrudLink(format!(
+                        "no link_update_delete on Deletion entry header and no fallback. Header: {:?}",
+                        header
+                    ))
+                }),
+            _ => Err(HolochainError::ErrorGeneric(format!(
+                "Can not get a deleted address from a {} aspect",
+                self.type_hint()
+            ))),
+        }
+    }
+
+    /// The entry address for the variants where it is directly present, without
+    /// failing. Intentionally differs from entry_address() for `Update` and `Deletion`:
+    /// there the address depends on the header's CRUD link and this returns None.
+    pub fn entry_address_unchecked(&self) -> Option<Address> {
+        match self {
+            EntryAspect::Update(_, _) | EntryAspect::Deletion(_) => None,
+            _ => self.entry_address().ok(),
+        }
+    }
+
+    /// For `Content` and `Update` aspects whose header is not the header of the
+    /// contained entry, the pair (header's entry address, entry's address).
+    /// None if they agree and for all other variants.
+    pub fn address_mismatch(&self) -> Option<(Address, Address)> {
+        match self {
+            EntryAspect::Content(entry, header) | EntryAspect::Update(entry, header) => {
+                let entry_address = entry.address();
+                if *header.entry_address() != entry_address {
+                    Some((header.entry_address().clone(), entry_address))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// True for `LinkAdd` and `LinkRemove` aspects whose link points from its base
+    /// to that same base, which is almost always a bug in the app.
+    pub fn is_self_link(&self) -> bool {
+        match self {
+            EntryAspect::LinkAdd(link_data, _) | EntryAspect::LinkRemove((link_data, _), _) => {
+                link_data.link.base() == link_data.link.target()
+            }
+            _ => false,
+        }
+    }
+
+    /// True for `Update` and `Deletion` aspects whose header's CRUD link points
+    /// at the header's own entry, i.e. an entry that replaces or deletes itself.
+    pub fn has_crud_cycle(&self) -> bool {
+        match self {
+            EntryAspect::Update(_, header) | EntryAspect::Deletion(header) => {
+                header.link_update_delete().as_ref() == Some(header.entry_address())
+            }
+            _ => false,
+        }
+    }
+
+    /// False for `Content` and `Update` aspects whose entry is not of the type
+    /// their header claims, e.g. an app entry under a `link_add` header.
+    /// All other variants carry no entry of their own and are always consistent.
+    pub fn content_type_consistent(&self) -> bool {
+        match self {
+            EntryAspect::Content(entry, header) | EntryAspect::Update(entry, header) => {
+                entry.entry_type() == *header.entry_type()
+            }
+            _ => true,
+        }
+    }
+
+    /// True if both aspects are link aspects (`LinkAdd` or `LinkRemove`, in any
+    /// combination) about the same link, i.e. same base, target, link type and tag.
+    pub fn same_target(&self, other: &EntryAspect) -> bool {
+        match (self.as_link(), other.as_link()) {
+            (Some(link), Some(other_link)) => link == other_link,
+            _ => false,
+        }
+    }
+
+    /// True if both aspects make the same statement, no matter who made it and when,
+    /// i.e. they are equal up to provenances (signatures and authors) and the header's
+    /// timestamp and chain links:
+    /// * link aspects compare their action kind and link (and the removed addresses for
+    ///   `LinkRemove`), as their link data records the author's chain
+    /// * all other aspects compare the header's entry type, entry address and CRUD link
+    pub fn content_eq(&self, other: &EntryAspect) -> bool {
+        if self.aspect_type() != other.aspect_type() {
+            return false;
+        }
+        match (self, other) {
+            (EntryAspect::LinkAdd(link_data, _), EntryAspect::LinkAdd(other_data, _)) => {
+                link_data.action_kind() == other_data.action_kind()
+                    && link_data.link() == other_data.link()
+            }
+            (
+                EntryAspect::LinkRemove((link_data, removed), _),
+                EntryAspect::LinkRemove((other_data, other_removed), _),
+            ) => {
+                link_data.action_kind() == other_data.action_kind()
+                    && link_data.link() == other_data.link()
+                    && removed == other_removed
+            }
+            _ => {
+                let (header, other_header) = (self.header(), other.header());
+                header.entry_type() == other_header.entry_type()
+                    && header.entry_address() == other_header.entry_address()
+                    && header.link_update_delete() == other_header.link_update_delete()
+            }
+        }
+    }
+
+    /// True if storing this aspect on top of `existing` would change nothing:
+    /// * an aspect with the same address is already in `existing`
+    /// * it is a `LinkAdd` of a link that `existing` adds already and does not remove
+    /// * it is a `LinkRemove` of a link that no `LinkAdd` in `existing` adds
+    pub fn is_noop_against(&self, existing: &[EntryAspect]) -> bool {
+        let address = self.address();
+        if existing.iter().any(|aspect| aspect.address() == address) {
+            return true;
+        }
+        fn is_link_add(aspect: &EntryAspect) -> bool {
+            match aspect {
+                EntryAspect::LinkAdd(_, _) => true,
+                _ => false,
+            }
+        }
+        let added = existing
+            .iter()
+            .any(|aspect| is_link_add(aspect) && aspect.same_target(self));
+        match self {
+            EntryAspect::LinkAdd(_, _) => {
+                let removed = existing
+                    .iter()
+                    .any(|aspect| !is_link_add(aspect) && aspect.same_target(self));
+                added && !removed
+            }
+            EntryAspect::LinkRemove(_, _) => !added,
+            _ => false,
+        }
+    }
+
+    /// The canonical form of this aspect: the addresses a `LinkRemove` removes are
+    /// sorted and deduplicated (their order carries no meaning), all other variants
+    /// are already canonical. Only self-consistent aspects have a canonical form,
+    /// so this errors with the failures of validate_self_consistency() otherwise.
+    pub fn normalize(self) -> Result<EntryAspect, HolochainError> {
+        self.validate_self_consistency(false)?;
+        Ok(self.with_sorted_removals())
+    }
+
+    /// The part of normalize() that changes anything: sorts and deduplicates the
+    /// addresses a `LinkRemove` removes, returns all other variants unchanged.
+    /// Unlike normalize() this does not check self-consistency.
+    pub fn with_sorted_removals(self) -> EntryAspect {
+        match self {
+            EntryAspect::LinkRemove((link_data, mut removed), header) => {
+                removed.sort();
+                removed.dedup();
+                EntryAspect::LinkRemove((link_data, removed), header)
+            }
+            other => other,
+        }
+    }
+
+    /// Coalesces all `LinkRemove` aspects about the same link (see same_target()) into
+    /// one that removes the union of their removed addresses, sorted and deduplicated
+    /// like normalize() does. The merged removal keeps the link data and header of the
+    /// first removal of its link and takes its place; all other aspects pass through
+    /// unchanged and in order.
+    /// The header of a merged removal is for the entry of that first removal only, so
+    /// merged removals are for local storage and not to be gossiped.
+    /// Errors if one of the removals is not self-consistent.
+    pub fn merge_link_removals(
+        aspects: Vec<EntryAspect>,
+    ) -> Result<Vec<EntryAspect>, HolochainError> {
+        let mut merged: Vec<EntryAspect> = Vec::with_capacity(aspects.len());
+        let mut positions: HashMap<Link, usize> = HashMap::new();
+        for aspect in aspects {
+            if let EntryAspect::LinkRemove(_, _) = aspect {
+                aspect.validate_self_consistency(false)?;
+            }
+            match aspect {
+                EntryAspect::LinkRemove((link_data, removed), header) => {
+                    if let Some(&position) = positions.get(link_data.link()) {
+                        if let EntryAspect::LinkRemove((_, all), _) = &mut merged[position] {
+                            all.extend(removed);
+                        }
+                        continue;
+                    }
+                    positions.insert(link_data.link().clone(), merged.len());
+                    merged.push(EntryAspect::LinkRemove((link_data, removed), header));
+                }
+                other => merged.push(other),
+            }
+        }
+        for position in positions.values() {
+            if let EntryAspect::LinkRemove((_, removed), _) = &mut merged[*position] {
+                removed.sort();
+                removed.dedup();
+            }
+        }
+        Ok(merged)
+    }
+
+    /// The content and address of this aspect in the current canonical form, i.e.
+    /// normalize()d and serialized as canonical_bytes(), whatever encoding it got
+    /// decoded from. Migrating stored aspects through this gives them stable addresses.
+    pub fn reserialize_stable(&self) -> Result<(Content, Address), HolochainError> {
+        let aspect = self.clone().normalize()?;
+        Ok((aspect.content(), aspect.address()))
+    }
+
+    /// The header's entry type as a stable key for grouping, independent of how
+    /// EntryType formats itself. App types render as `app:<name>`, system types as
+    /// `sys:<name>`: `sys:dna`, `sys:agent_id`, `sys:deletion`, `sys:link_add`,
+    /// `sys:link_remove`, `sys:link_list`, `sys:chain_header`, `sys:chain_migrate`,
+    /// `sys:cap_token_grant` and `sys:cap_token_claim`.
+    pub fn entry_type_string(&self) -> String {
+        let sys = match self.header().entry_type() {
+            EntryType::App(app_entry_type) => return format!("app:{}", app_entry_type.to_string()),
+            EntryType::Dna => "dna",
+            EntryType::AgentId => "agent_id",
+            EntryType::Deletion => "deletion",
+            EntryType::LinkAdd => "link_add",
+            EntryType::LinkRemove => "link_remove",
+            EntryType::LinkList => "link_list",
+            EntryType::ChainHeader => "chain_header",
+            EntryType::ChainMigrate => "chain_migrate",
+            EntryType::CapTokenGrant => "cap_token_grant",
+            EntryType::CapTokenClaim => "cap_token_claim",
+        };
+        format!("sys:{}", sys)
+    }
+
+    /// How long before the `clock`'s now the aspect's header got timestamped. None if the
+    /// header's timestamp is after now, as then the age of the aspect can't be told
+    /// (e.g. because of clock skew between agents).
+    pub fn age(&self, clock: &dyn Clock) -> Option<Duration> {
+        DateTime::<FixedOffset>::from(&clock.now())
+            .signed_duration_since(DateTime::<FixedOffset>::from(self.header().timestamp()))
+            .to_std()
+            .ok()
+    }
+
+    /// True if the aspect is older than `ttl` by `clock`, see age().
+    /// Aspects that can't be dated never expire.
+    pub fn is_expired(&self, ttl: Duration, clock: &dyn Clock) -> bool {
+        match self.age(clock) {
+            Some(age) => age > ttl,
+            None => false,
+        }
+    }
+
+    /// Key for a deterministic replay order of aspects from several agents,
+    /// see ReplayKey for its guarantees.
+    pub fn replay_key(&self) -> ReplayKey {
+        let header = self.header();
+        ReplayKey {
+            timestamp: *header.timestamp(),
+            author: self.author(),
+            chain_position: if header.link().is_none() { 0 } else { 1 },
+            header_address: header.address(),
+        }
+    }
+
+    /// Stable shard index in `0..shards` for this aspect, derived from entry_address().
+    /// The address string's UTF-8 bytes are hashed with 64-bit FNV-1a (offset basis
+    /// 0xcbf29ce484222325, prime 0x100000001b3), so the result is the same across
+    /// processes and architectures, unlike std's DefaultHasher.
+    pub fn shard_key(&self, shards: u64) -> Result<u64, HolochainError> {
+        if shards == 0 {
+            return Err(HolochainError::ErrorGeneric(String::from(
+                "Can not compute a shard key for zero shards",
+            )));
+        }
+        Ok(fnv1a_64(self.entry_address()?.to_string().as_bytes()) % shards)
+    }
+
+    /// Compact key for in-memory dedup maps: the first 16 bytes of the SHA2-256 digest
+    /// of the UTF-8 bytes of the address string. Stable across processes, but unlike
+    /// the address not collision resistant enough to identify aspects by.
+    pub fn fingerprint(&self) -> Result<[u8; 16], HolochainError> {
+        let multihash = multihash::encode(
+            HashAlgorithm::SHA2256,
+            self.address().to_string().as_bytes(),
+        )
+        .map_err(|error| HolochainError::ErrorGeneric(error.to_string()))?;
+        // skip the multihash prefix of hash function code and digest length
+        let mut fingerprint = [0; 16];
+        fingerprint.copy_from_slice(&multihash[2..18]);
+        Ok(fingerprint)
+    }
+
+    /// Applies `f` to the entry of `Content` and `Update` aspects, keeping the header.
+    /// Fails if the new entry's address is not the one the header points to.
+    /// All other variants are returned unchanged.
+    pub fn map_entry(
+        self,
+        f: impl FnOnce(Entry) -> Result<Entry, HolochainError>,
+    ) -> Result<EntryAspect, HolochainError> {
+        let (entry, header, is_update) = match self {
+            EntryAspect::Content(entry, header) => (entry, header, false),
+            EntryAspect::Update(entry, header) => (entry, header, true),
+            other => return Ok(other),
+        };
+        let entry = f(entry)?;
+        if entry.address() != *header.entry_address() {
+            return Err(HolochainError::ErrorGeneric(format!(
+                "Mapped entry {} does not match header entry address {}",
+                entry.address(),
+                header.entry_address()
+            )));
+        }
+        Ok(if is_update {
+            EntryAspect::Update(entry, header)
+        } else {
+            EntryAspect::Content(entry, header)
+        })
+    }
+
+    /// Drops the entry of `Content` and `Update` aspects, keeping only their header
+    /// as a `Header` aspect, e.g. to reclaim storage while keeping the proof of
+    /// authorship. Its entry_address() is the header's address.
+    /// `Header` and `Deletion` aspects carry no entry and are returned unchanged.
+    /// Link aspects fail, as a header alone would lose the link.
+    pub fn purge_payload(self) -> Result<EntryAspect, HolochainError> {
+        match self {
+            EntryAspect::Content(_, header) | EntryAspect::Update(_, header) => {
+                Ok(EntryAspect::Header(header))
+            }
+            EntryAspect::Header(header) => Ok(EntryAspect::Header(header)),
+            EntryAspect::Deletion(header) => Ok(EntryAspect::Deletion(header)),
+            link => Err(HolochainError::ErrorGeneric(format!(
+                "Can not purge the payload of a {} aspect without losing its link",
+                link.type_hint()
+            ))),
+        }
+    }
+
+    /// The link aspect with its link's tag replaced by `new_tag`, keeping base, target,
+    /// link type and the header. Fails for variants that are not link aspects.
+    /// As the link entry changes, so do the aspect's address and the address the
+    /// header should point to: the result is not self-consistent until it gets a
+    /// new header for the re-tagged entry.
+    pub fn with_link_tag(self, new_tag: &str) -> Result<EntryAspect, HolochainError> {
+        fn retag(mut link_data: LinkData, new_tag: &str) -> LinkData {
+            link_data.link = Link::new(
+                link_data.link.base(),
+                link_data.link.target(),
+                link_data.link.link_type(),
+                new_tag,
+            );
+            link_data
+        }
+        match self {
+            EntryAspect::LinkAdd(link_data, header) => {
+                Ok(EntryAspect::LinkAdd(retag(link_data, new_tag), header))
+            }
+            EntryAspect::LinkRemove((link_data, removed), header) => Ok(EntryAspect::LinkRemove(
+                (retag(link_data, new_tag), removed),
+                header,
+            )),
+            other => Err(HolochainError::ErrorGeneric(format!(
+                "Can not change the link tag of a {} aspect",
+                other.type_hint()
+            ))),
+        }
+    }
+
+    /// Applies `f` to the link of `LinkAdd` and `LinkRemove` aspects, e.g. to move links
+    /// to a new base during a migration. All other variants are returned unchanged.
+    /// Like with with_link_tag() the link entry changes, and with it the aspect's
+    /// address: the result needs a new header for the mapped entry to be self-consistent.
+    pub fn map_link(
+        self,
+        f: impl FnOnce(Link) -> Result<Link, HolochainError>,
+    ) -> Result<EntryAspect, HolochainError> {
+        Ok(match self {
+            EntryAspect::LinkAdd(mut link_data, header) => {
+                link_data.link = f(link_data.link)?;
+                EntryAspect::LinkAdd(link_data, header)
+            }
+            EntryAspect::LinkRemove((mut link_data, removed), header) => {
+                link_data.link = f(link_data.link)?;
+                EntryAspect::LinkRemove((link_data, removed), header)
+            }
+            other => other,
+        })
+    }
+
+    /// Puts a copy of this aspect into `store`, e.g. when mirroring aspects
+    /// between stores, and returns its address.
+    pub fn clone_into_store(
+        &self,
+        store: &mut impl AspectStore,
+    ) -> Result<Address, HolochainError> {
+        self.clone().into_store(store)
+    }
+
+    /// Same as clone_into_store() for owned aspects, without the copy.
+    pub fn into_store(self, store: &mut impl AspectStore) -> Result<Address, HolochainError> {
+        store.put(self)
+    }
+
+    /// Pretty-printed JSON of this aspect for debugging output and fixtures.
+    /// NOT for addressing: the address is always computed from the compact `content()`.
+    pub fn to_pretty_json(&self) -> Result<String, HolochainError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// One line human readable summary of what this aspect states, for debugging,
+    /// with addresses shortened by short_address(). Entry payloads are never included.
+    pub fn describe(&self) -> String {
+        fn crud_base(header: &ChainHeader) -> String {
+            header
+                .link_update_delete()
+                .map(|base| short_address(&base))
+                .unwrap_or_else(|| String::from("<no CRUD link>"))
+        }
+        let header = self.header();
+        match self {
+            EntryAspect::Content(entry, _) => format!(
+                "content: {} entry {}",
+                entry.entry_type(),
+                short_address(header.entry_address())
+            ),
+            EntryAspect::Header(_) => format!(
+                "header {} of {} entry {}",
+                short_address(&header.address()),
+                header.entry_type(),
+                short_address(header.entry_address())
+            ),
+            EntryAspect::LinkAdd(link_data, _) => format!(
+                "link_add: {} -> {} [type: {}, tag: {}]",
+                short_address(link_data.link.base()),
+                short_address(link_data.link.target()),
+                link_data.link.link_type(),
+                link_data.link.tag()
+            ),
+            EntryAspect::LinkRemove((link_data, removed), _) => format!(
+                "link_remove: {} -> {} [type: {}, tag: {}], removing {} link add(s)",
+                short_address(link_data.link.base()),
+                short_address(link_data.link.target()),
+                link_data.link.link_type(),
+                link_data.link.tag(),
+                removed.len()
+            ),
+            EntryAspect::Update(_, _) => format!(
+                "update: {} replaced by {}",
+                crud_base(header),
+                short_address(header.entry_address())
+            ),
+            EntryAspect::Deletion(_) => format!("deletion: {} deleted", crud_base(header)),
+        }
+    }
+
+    /// Key-value fields describing this aspect for structured logging, with
+    /// addresses shortened by short_address(). Entry payloads are never included.
+    /// Keys present for every aspect: `aspect_type`, `entry_type`, `header_address`.
+    /// Present when applicable: `entry_address`, `author` (first provenance),
+    /// `crud_link`, and for link aspects `link_base`, `link_target`, `link_type`, `link_tag`.
+    pub fn to_debug_map(&self) -> BTreeMap<String, String> {
+        let header = self.header();
+        let mut map = BTreeMap::new();
+        map.insert(String::from("aspect_type"), self.type_hint());
+        map.insert(String::from("entry_type"), header.entry_type().to_string());
+        map.insert(
+            String::from("header_address"),
+            short_address(&self.header_address()),
+        );
+        if let Ok(entry_address) = self.entry_address() {
+            map.insert(String::from("entry_address"), short_address(&entry_address));
+        }
+        if let Some(provenance) = header.provenances().first() {
+            map.insert(String::from("author"), short_address(&provenance.source()));
+        }
+        if let Some(crud_link) = header.link_update_delete() {
+            map.insert(String::from("crud_link"), short_address(&crud_link));
+        }
+        match self {
+            EntryAspect::LinkAdd(link_data, _) | EntryAspect::LinkRemove((link_data, _), _) => {
+                let link = link_data.link();
+                map.insert(String::from("link_base"), short_address(link.base()));
+                map.insert(String::from("link_target"), short_address(link.target()));
+                map.insert(String::from("link_type"), link.link_type().clone());
+                map.insert(String::from("link_tag"), link.tag().clone());
+            }
+            _ => (),
+        }
+        map
+    }
+
+    /// Size of the serialized aspect in bytes, i.e. of canonical_bytes().
+    pub fn serialized_size(&self) -> Result<usize, HolochainError> {
+        Ok(self.canonical_bytes()?.len())
+    }
+
+    /// Approximate size of the payload alone, i.e. serialized_size() without the header:
+    /// the serialized size of the entry of `Content` and `Update` aspects, of the link
+    /// data of `LinkAdd` and of the link data and removed addresses of `LinkRemove`.
+    /// 0 for `Header` and `Deletion` aspects, which have no payload.
+    pub fn approx_payload_bytes(&self) -> usize {
+        fn size<T: Serialize>(payload: &T) -> usize {
+            serde_json::to_vec(payload)
+                .map(|bytes| bytes.len())
+                .unwrap_or(0)
+        }
+        match self {
+            EntryAspect::Content(entry, _) | EntryAspect::Update(entry, _) => size(entry),
+            EntryAspect::LinkAdd(link_data, _) => size(link_data),
+            EntryAspect::LinkRemove(removal, _) => size(removal),
+            EntryAspect::Header(_) | EntryAspect::Deletion(_) => 0,
+        }
+    }
+
+    /// How urgently the aspect should be gossiped, higher first. Tombstones go first,
+    /// so that peers do not keep serving what got deleted or removed, then updates,
+    /// links, contents and finally headers:
+    /// `Deletion` 5, `LinkRemove` 4, `Update` 3, `LinkAdd` 2, `Content` 1, `Header` 0.
+    pub fn gossip_priority(&self) -> u8 {
+        match self {
+            EntryAspect::Deletion(_) => 5,
+            EntryAspect::LinkRemove(_, _) => 4,
+            EntryAspect::Update(_, _) => 3,
+            EntryAspect::LinkAdd(_, _) => 2,
+            EntryAspect::Content(_, _) => 1,
+            EntryAspect::Header(_) => 0,
+        }
+    }
+
+    /// Same as `content()` but refuses aspects whose serialized form is larger
+    /// than `max` bytes with `HolochainError::AspectTooLarge`.
+    /// `content()` itself stays uncapped for compatibility.
+    /// The size is that of canonical_bytes(), which only get turned into `Content`
+    /// once they passed the cap.
+    pub fn to_content_capped(&self, max: usize) -> Result<Content, HolochainError> {
+        let bytes = self.canonical_bytes()?;
+        if bytes.len() > max {
+            return Err(HolochainError::AspectTooLarge(bytes.len(), max));
+        }
+        let json = String::from_utf8(bytes)
+            .map_err(|error| HolochainError::SerializationError(error.to_string()))?;
+        Ok(JsonString::from_json(&json))
+    }
+
+    /// Addresses that have to be known before this aspect can be validated:
+    /// the CRUD base for `Update`/`Deletion`, base and target for link aspects
+    /// plus the removed link addresses for `LinkRemove`.
+    pub fn dependencies(&self) -> Vec<Address> {
+        match self {
+            EntryAspect::Content(_, _) | EntryAspect::Header(_) => Vec::new(),
+            EntryAspect::LinkAdd(link_data, _) => vec![
+                link_data.link.base().clone(),
+                link_data.link.target().clone(),
+            ],
+            EntryAspect::LinkRemove((link_data, removed), _) => {
+                let mut dependencies = vec![
+                    link_data.link.base().clone(),
+                    link_data.link.target().clone(),
+                ];
+                dependencies.extend(removed.iter().cloned());
+                dependencies
+            }
+            EntryAspect::Update(_, header) | EntryAspect::Deletion(header) => {
+                header.link_update_delete().into_iter().collect()
+            }
+        }
+    }
+
+    /// Hash over exactly the fields that can change a validation verdict, so that a
+    /// verdict cached under this fingerprint can be reused for another aspect with the
+    /// same fingerprint. Included are:
+    /// * the aspect type (`type_hint()`)
+    /// * the header's entry type
+    /// * the authors, i.e. the source addresses of all header provenances
+    /// * the payload: the entry address for `Content`/`Update`, the header's entry
+    ///   address for `Header`, the action kind and link (base, target, type, tag) for
+    ///   link aspects, nothing for `Deletion`
+    /// * `dependencies()`, in order
+    ///
+    /// Signatures, timestamps and the header's chain links are left out as they don't
+    /// change validity of the aspect's content.
+    pub fn validation_fingerprint(&self) -> Result<Address, HolochainError> {
+        let header = self.header();
+        let authors: Vec<Address> = header
+            .provenances()
+            .iter()
+            .map(|provenance| provenance.source())
+            .collect();
+        let payload = match self {
+            EntryAspect::Content(entry, _) | EntryAspect::Update(entry, _) => {
+                json!({ "entry": entry.address() })
+            }
+            EntryAspect::Header(header) => json!({ "entry": header.entry_address() }),
+            EntryAspect::LinkAdd(link_data, _) | EntryAspect::LinkRemove((link_data, _), _) => {
+                json!({ "action_kind": link_data.action_kind(), "link": link_data.link() })
+            }
+            EntryAspect::Deletion(_) => serde_json::Value::Null,
+        };
+        let fingerprint = json!({
+            "aspect_type": self.type_hint(),
+            "entry_type": header.entry_type(),
+            "authors": authors,
+            "payload": payload,
+            "dependencies": self.dependencies(),
+        });
+        Ok(Address::encode_from_str(
+            &serde_json::to_string(&fingerprint)?,
+            HashAlgorithm::SHA2256,
+        ))
+    }
+
+    /// Flat JSON projection of the aspect for search indexes, without the payload:
+    /// `aspect_type` (type_hint()), `address`, `entry_address` and `entry_type`
+    /// (entry_type_string()) of the header, `header_address`, `author` (null if
+    /// unknown) and `timestamp`. See to_index_value_with() for one with payload.
+    pub fn to_index_value(&self) -> Result<serde_json::Value, HolochainError> {
+        self.to_index_value_with(false)
+    }
+
+    /// to_index_value(), with the payload inlined if `include_payload` is set:
+    /// `entry` for `Content` and `Update` aspects, `link_data` for link aspects
+    /// and `removed` (the removed addresses) for `LinkRemove`.
+    /// `Header` and `Deletion` aspects have no payload.
+    /// Only for trusted indexers, the payload is what to_index_value() keeps private.
+    pub fn to_index_value_with(
+        &self,
+        include_payload: bool,
+    ) -> Result<serde_json::Value, HolochainError> {
+        let header = self.header();
+        let mut value = json!({
+            "aspect_type": self.type_hint(),
+            "address": self.address(),
+            "entry_address": header.entry_address(),
+            "entry_type": self.entry_type_string(),
+            "header_address": header.address(),
+            "author": self.author(),
+            "timestamp": header.timestamp().to_string(),
+        });
+        if include_payload {
+            let mut payload = serde_json::Map::new();
+            match self {
+                EntryAspect::Content(entry, _) | EntryAspect::Update(entry, _) => {
+                    payload.insert(String::from("entry"), serde_json::to_value(entry)?);
+                }
+                EntryAspect::LinkAdd(link_data, _) => {
+                    payload.insert(String::from("link_data"), serde_json::to_value(link_data)?);
+                }
+                EntryAspect::LinkRemove((link_data, removed), _) => {
+                    payload.insert(String::from("link_data"), serde_json::to_value(link_data)?);
+                    payload.insert(String::from("removed"), serde_json::to_value(removed)?);
+                }
+                EntryAspect::Header(_) | EntryAspect::Deletion(_) => (),
+            }
+            if let serde_json::Value::Object(fields) = &mut value {
+                fields.extend(payload);
+            }
+        }
+        Ok(value)
+    }
+
+    /// The canonical JSON of the aspect (see canonical_bytes()) with an extra
+    /// `_addresses` field holding its `address`, `header_address` and `entry_address`
+    /// (null if that fails, see entry_address()).
+    /// This is a debugging and export format only: it does not decode as an aspect.
+    pub fn to_json_with_addresses(&self) -> Result<serde_json::Value, HolochainError> {
+        let mut value: serde_json::Value = serde_json::from_slice(&self.canonical_bytes()?)?;
+        let addresses = json!({
+            "address": self.address(),
+            "header_address": self.header_address(),
+            "entry_address": self.entry_address().ok(),
+        });
+        if let serde_json::Value::Object(fields) = &mut value {
+            fields.insert(String::from("_addresses"), addresses);
+        }
+        Ok(value)
+    }
+
+    /// Best-effort recovery decode for aspects that some legacy producers emitted
+    /// without the enum tag, i.e. as the bare variant payload.
+    /// Content that decodes strictly is returned as is. Otherwise the variant is
+    /// inferred from the structure of the JSON:
+    /// * `[entry, header]` → `Content` (or `Update` for an app entry whose header has a
+    ///   CRUD link, which is ambiguous and therefore an error)
+    /// * `[link_data, header]` → `LinkAdd`
+    /// * `[[link_data, [address, ..]], header]` → `LinkRemove`
+    /// * `header` → `Deletion` for deletion headers with a CRUD link, `Header` otherwise
+    ///
+    /// Errors if no variant or more than one variant fits.
+    pub fn from_content_structural(content: &Content) -> Result<EntryAspect, HolochainError> {
+        if let Ok(aspect) = EntryAspect::try_from(content.to_owned()) {
+            return Ok(aspect);
+        }
+        let value: serde_json::Value = serde_json::from_str(&String::from(content.to_owned()))?;
+        let mut candidates = structural_candidates(value);
+        match candidates.len() {
+            1 => Ok(candidates.remove(0)),
+            0 => Err(HolochainError::SerializationError(String::from(
+                "Could not infer an EntryAspect variant from the structure of the content",
+            ))),
+            _ => Err(HolochainError::SerializationError(format!(
+                "Ambiguous untagged EntryAspect, could be any of: {}",
+                candidates
+                    .iter()
+                    .map(EntryAspect::type_hint)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    /// Decodes `content` as forgiving as `mode` allows, see DecodeMode.
+    /// `Ok(None)` means the content is an aspect of a variant this version does not
+    /// support (only in the modes that tolerate unknown variants).
+    pub fn decode(
+        content: &Content,
+        mode: DecodeMode,
+    ) -> Result<Option<EntryAspect>, HolochainError> {
+        if mode == DecodeMode::Strict {
+            return Ok(Some(EntryAspect::try_from_content(content)?));
+        }
+        let error = match EntryAspect::try_from(content.to_owned()) {
+            Ok(aspect) => return Ok(Some(aspect)),
+            Err(error) => HolochainError::from(error),
+        };
+        let value: serde_json::Value = serde_json::from_str(&String::from(content.to_owned()))?;
+        if let Some((tag, payload)) = value.as_object().and_then(|object| {
+            if object.len() == 1 {
+                object.iter().next()
+            } else {
+                None
+            }
+        }) {
+            if !VARIANT_NAMES.contains(&tag.as_str()) {
+                return Ok(None);
+            }
+            if mode >= DecodeMode::RepairLegacy && tag == "Deletion" {
+                if let Ok((deleted, header)) =
+                    serde_json::from_value::<(Address, ChainHeader)>(payload.clone())
+                {
+                    return repair_legacy_deletion(deleted, header).map(Some);
+                }
+            }
+        }
+        if mode >= DecodeMode::InferStructural {
+            return EntryAspect::from_content_structural(content).map(Some);
+        }
+        Err(error)
+    }
+}
+
+/// The deleted address used to be stored next to the header. It can't be moved into
+/// a (signed) header, so this only works for headers that carry it as CRUD link.
+fn repair_legacy_deletion(
+    deleted: Address,
+    header: ChainHeader,
+) -> Result<EntryAspect, HolochainError> {
+    if header.link_update_delete() == Some(deleted.clone()) {
+        Ok(EntryAspect::Deletion(header))
+    } else {
+        Err(HolochainError::SerializationError(format!(
+            "Legacy deletion of {} has a header with CRUD link {:?}",
+            deleted,
+            header.link_update_delete()
+        )))
+    }
+}
+
+fn structural_candidates(value: serde_json::Value) -> Vec<EntryAspect> {
+    let mut candidates = Vec::new();
+    if let Ok(header) = serde_json::from_value::<ChainHeader>(value.clone()) {
+        if *header.entry_type() == EntryType::Deletion && header.link_update_delete().is_some() {
+            candidates.push(EntryAspect::Deletion(header));
+        } else {
+            candidates.push(EntryAspect::Header(header));
+        }
+    }
+    if let Ok((entry, header)) = serde_json::from_value::<(Entry, ChainHeader)>(value.clone()) {
+        if entry.entry_type().is_app() && header.link_update_delete().is_some() {
+            candidates.push(EntryAspect::Update(entry.clone(), header.clone()));
+        }
+        candidates.push(EntryAspect::Content(entry, header));
+    }
+    if let Ok((link_data, header)) =
+        serde_json::from_value::<(LinkData, ChainHeader)>(value.clone())
+    {
+        candidates.push(EntryAspect::LinkAdd(link_data, header));
+    }
+    if let Ok((removal, header)) =
+        serde_json::from_value::<((LinkData, Vec<Address>), ChainHeader)>(value)
+    {
+        candidates.push(EntryAspect::LinkRemove(removal, header));
+    }
+    candidates
+}
+
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Shortened form of an address for log output: the first 8 and last 4 characters.
+/// Addresses that would not get shorter by this are returned in full.
+pub fn short_address(address: &Address) -> String {
+    let address = address.to_string();
+    let chars: Vec<char> = address.chars().collect();
+    if chars.len() <= 15 {
+        address
+    } else {
+        format!(
+            "{}...{}",
+            chars[..8].iter().collect::<String>(),
+            chars[chars.len() - 4..].iter().collect::<String>()
+        )
+    }
+}
+
+/// Describes what the DHT meta row `eav` means: the aspect whose address is the
+/// row's value is fetched from `store` and described by EntryAspect::describe().
+/// Fails if the aspect can't be found or if it is not the aspect that was recorded
+/// under the row's entity and attribute (see EntryAspect::to_eav()).
+pub fn describe_eav(
+    eav: &EntityAttributeValueIndex,
+    store: &impl AspectStore,
+) -> Result<String, HolochainError> {
+    let aspect = store.get(&eav.value())?.ok_or_else(|| {
+        HolochainError::ErrorGeneric(format!(
+            "Aspect {} of EAV row for {} could not be found",
+            eav.value(),
+            eav.entity()
+        ))
+    })?;
+    let recorded = aspect.to_eav()?;
+    let matches = match &recorded {
+        Some(recorded) => {
+            recorded.entity() == eav.entity() && recorded.attribute() == eav.attribute()
+        }
+        None => false,
+    };
+    if !matches {
+        return Err(HolochainError::ErrorGeneric(format!(
+            "EAV row for {} does not record aspect {} ({})",
+            eav.entity(),
+            eav.value(),
+            aspect.describe()
+        )));
+    }
+    Ok(aspect.describe())
 }
 
 fn format_header(header: &ChainHeader) -> String {
     format!(
         "Header[type: {}, crud_link: {:?}]",
         header.entry_type(),
-        header.link_update_delete()
+        header.link_update_delete().as_ref().map(short_address)
     )
 }
 impl fmt::Debug for EntryAspect {
@@ -132,7 +1402,7 @@ impl fmt::Debug for EntryAspect {
             EntryAspect::Content(entry, header) => write!(
                 f,
                 "EntryAspect::Content({}, {})",
-                entry.address(),
+                short_address(&entry.address()),
                 format_header(header)
             ),
             EntryAspect::Header(header) => {
@@ -141,8 +1411,8 @@ impl fmt::Debug for EntryAspect {
             EntryAspect::LinkAdd(link_data, header) => write!(
                 f,
                 "EntryAspect::LinkAdd({} -> {} [tag: {}, type: {}], {})",
-                link_data.link.base(),
-                link_data.link.target(),
+                short_address(link_data.link.base()),
+                short_address(link_data.link.target()),
                 link_data.link.tag(),
                 link_data.link.link_type(),
                 format_header(header)
@@ -150,8 +1420,8 @@ impl fmt::Debug for EntryAspect {
             EntryAspect::LinkRemove((link_data, _), header) => write!(
                 f,
                 "EntryAspect::LinkRemove({} -> {} [tag: {}, type: {}], top_chain_header:{}, remove_header: {})",
-                link_data.link.base(),
-                link_data.link.target(),
+                short_address(link_data.link.base()),
+                short_address(link_data.link.target()),
                 link_data.link.tag(),
                 link_data.link.link_type(),
                 format_header(&link_data.top_chain_header),
@@ -160,7 +1430,7 @@ impl fmt::Debug for EntryAspect {
             EntryAspect::Update(entry, header) => write!(
                 f,
                 "EntryAspect::Update({}, {})",
-                entry.address(),
+                short_address(&entry.address()),
                 format_header(header)
             ),
             EntryAspect::Deletion(header) => {
@@ -170,6 +1440,12 @@ impl fmt::Debug for EntryAspect {
     }
 }
 
+impl AsRef<ChainHeader> for EntryAspect {
+    fn as_ref(&self) -> &ChainHeader {
+        self.header()
+    }
+}
+
 #[allow(clippy::derive_hash_xor_eq)]
 // This clippy lint stresses the point that impls of Hash and PartialEq have to agree,
 // that is ensure that: k1 == k2 ⇒ hash(k1) == hash(k2).
@@ -182,3 +1458,1299 @@ impl Hash for EntryAspect {
         self.type_hint().hash(state);
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::{test_chain_header, test_chain_header_with_sig, test_provenances},
+        entry::{entry_type::test_app_entry_type, test_entry, test_entry_b, test_entry_value_b},
+        error::ErrorKind,
+        link::link_data::example_link_add,
+        network::aspect_store::MemoryAspectStore,
+        signature::Signature,
+        time::{test_iso_8601, FixedClock},
+    };
+    use std::collections::BTreeSet;
+
+    pub fn test_content_aspect() -> EntryAspect {
+        EntryAspect::Content(test_entry(), test_chain_header())
+    }
+
+    pub fn test_link_add_aspect() -> EntryAspect {
+        let link_add = example_link_add();
+        let header = test_header_for(&Entry::LinkAdd(link_add.clone()), &test_iso_8601());
+        EntryAspect::LinkAdd(link_add, header)
+    }
+
+    pub fn test_update_aspect() -> EntryAspect {
+        let entry = Entry::App(test_app_entry_type(), test_entry_value_b());
+        let header = ChainHeader::new(
+            &entry.entry_type(),
+            &entry.address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &Some(test_entry().address()),
+            &test_iso_8601(),
+        );
+        EntryAspect::Update(entry, header)
+    }
+
+    pub fn test_deletion_aspect() -> EntryAspect {
+        EntryAspect::Deletion(ChainHeader::new(
+            &EntryType::Deletion,
+            &test_entry_b().address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &Some(test_entry().address()),
+            &test_iso_8601(),
+        ))
+    }
+
+    /// header for `entry` that is otherwise like `test_chain_header()`
+    pub fn test_header_for(entry: &Entry, timestamp: &Iso8601) -> ChainHeader {
+        ChainHeader::new(
+            &entry.entry_type(),
+            &entry.address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &None,
+            timestamp,
+        )
+    }
+
+    #[test]
+    fn to_content_capped_test() {
+        let aspect = test_content_aspect();
+        let size = String::from(aspect.content()).len();
+
+        assert_eq!(aspect.to_content_capped(size), Ok(aspect.content()));
+        assert_eq!(
+            aspect.to_content_capped(size - 1),
+            Err(HolochainError::AspectTooLarge(size, size - 1)),
+        );
+    }
+
+    #[test]
+    fn validation_fingerprint_test() {
+        let aspect = test_content_aspect();
+        let fingerprint = aspect.validation_fingerprint().unwrap();
+
+        let later = EntryAspect::Content(
+            test_entry(),
+            test_header_for(&test_entry(), &Iso8601::from(1_600_000_000)),
+        );
+        assert_ne!(aspect.address(), later.address());
+        assert_eq!(fingerprint, later.validation_fingerprint().unwrap());
+
+        let entry_b = Entry::App(test_app_entry_type(), test_entry_value_b());
+        let other_entry =
+            EntryAspect::Content(entry_b.clone(), test_header_for(&entry_b, &test_iso_8601()));
+        assert_ne!(fingerprint, other_entry.validation_fingerprint().unwrap());
+    }
+
+    fn untagged_content<T: Serialize>(payload: &T) -> Content {
+        JsonString::from_json(&serde_json::to_string(payload).unwrap())
+    }
+
+    #[test]
+    fn entry_type_string_test() {
+        assert_eq!(
+            test_content_aspect().entry_type_string(),
+            "app:testEntryType"
+        );
+        assert_eq!(test_link_add_aspect().entry_type_string(), "sys:link_add");
+        assert_eq!(test_deletion_aspect().entry_type_string(), "sys:deletion");
+    }
+
+    #[test]
+    fn try_from_value_test() {
+        for aspect in vec![
+            test_content_aspect(),
+            test_link_add_aspect(),
+            test_update_aspect(),
+            test_deletion_aspect(),
+        ] {
+            let content = aspect.content();
+            let value: serde_json::Value =
+                serde_json::from_str(&String::from(content.clone())).unwrap();
+            assert_eq!(
+                EntryAspect::try_from(value).unwrap(),
+                EntryAspect::try_from(content).unwrap()
+            );
+        }
+        assert!(EntryAspect::try_from(json!({ "Content": "not an aspect" })).is_err());
+    }
+
+    #[test]
+    fn from_content_structural_test() {
+        let header = test_chain_header();
+        let link_add = example_link_add();
+        let removal = (example_link_add(), vec![test_entry().address()]);
+        let deletion_header = ChainHeader::new(
+            &EntryType::Deletion,
+            &test_entry_b().address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &Some(test_entry().address()),
+            &test_iso_8601(),
+        );
+
+        for (content, expected) in vec![
+            (
+                untagged_content(&(test_entry(), header.clone())),
+                EntryAspect::Content(test_entry(), header.clone()),
+            ),
+            (
+                untagged_content(&(link_add.clone(), header.clone())),
+                EntryAspect::LinkAdd(link_add, header.clone()),
+            ),
+            (
+                untagged_content(&(removal.clone(), header.clone())),
+                EntryAspect::LinkRemove(removal, header.clone()),
+            ),
+            (
+                untagged_content(&header),
+                EntryAspect::Header(header.clone()),
+            ),
+            (
+                untagged_content(&deletion_header),
+                EntryAspect::Deletion(deletion_header.clone()),
+            ),
+            // tagged content still decodes strictly
+            (test_content_aspect().content(), test_content_aspect()),
+        ] {
+            assert_eq!(EntryAspect::from_content_structural(&content), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn decode_test() {
+        let modes = [
+            DecodeMode::Strict,
+            DecodeMode::TolerateUnknown,
+            DecodeMode::RepairLegacy,
+            DecodeMode::InferStructural,
+        ];
+        let to_content = |value: serde_json::Value| JsonString::from_json(&value.to_string());
+        let empty_removal =
+            EntryAspect::LinkRemove((example_link_add(), Vec::new()), test_chain_header());
+        let unknown = to_content(json!({ "Migration": { "from": "somewhere" } }));
+        let deletion_header = test_deletion_aspect().header().clone();
+        let legacy_deletion = to_content(json!({
+            "Deletion": [test_entry().address(), deletion_header.clone()]
+        }));
+        let untagged = untagged_content(&(test_entry(), test_chain_header()));
+
+        for mode in modes.iter() {
+            let decode = |content: &Content| EntryAspect::decode(content, *mode);
+            assert_eq!(
+                decode(&test_content_aspect().content()),
+                Ok(Some(test_content_aspect()))
+            );
+            assert_eq!(
+                decode(&empty_removal.content()).ok(),
+                if *mode >= DecodeMode::TolerateUnknown {
+                    Some(Some(empty_removal.clone()))
+                } else {
+                    None
+                }
+            );
+            assert_eq!(
+                decode(&unknown).ok(),
+                if *mode >= DecodeMode::TolerateUnknown {
+                    Some(None)
+                } else {
+                    None
+                }
+            );
+            assert_eq!(
+                decode(&legacy_deletion).ok(),
+                if *mode >= DecodeMode::RepairLegacy {
+                    Some(Some(EntryAspect::Deletion(deletion_header.clone())))
+                } else {
+                    None
+                }
+            );
+            assert_eq!(
+                decode(&untagged).ok(),
+                if *mode >= DecodeMode::InferStructural {
+                    Some(Some(test_content_aspect()))
+                } else {
+                    None
+                }
+            );
+        }
+
+        // the legacy address has to agree with the header
+        let mismatch = to_content(json!({
+            "Deletion": [test_entry_b().address(), deletion_header]
+        }));
+        assert!(EntryAspect::decode(&mismatch, DecodeMode::RepairLegacy).is_err());
+    }
+
+    #[test]
+    fn from_content_structural_ambiguous_test() {
+        // an app entry with a CRUD-linked header could be Content as well as Update
+        let header = test_chain_header_with_sig("sig", Some(test_entry_b().address()));
+        let content = untagged_content(&(test_entry(), header));
+        assert!(EntryAspect::from_content_structural(&content).is_err());
+        assert!(EntryAspect::from_content_structural(&JsonString::from_json("{}")).is_err());
+    }
+
+    #[test]
+    fn entry_address_unchecked_test() {
+        assert_eq!(
+            test_content_aspect().entry_address_unchecked(),
+            Some(test_entry().address())
+        );
+        assert_eq!(
+            test_link_add_aspect().entry_address_unchecked(),
+            Some(example_link_add().link().base().clone())
+        );
+        assert_eq!(test_update_aspect().entry_address_unchecked(), None);
+        assert_eq!(test_deletion_aspect().entry_address_unchecked(), None);
+        assert!(test_deletion_aspect().entry_address().is_ok());
+    }
+
+    #[test]
+    fn aspect_type_test() {
+        assert_eq!(AspectType::ALL.len(), 6);
+        let distinct: BTreeSet<AspectType> = AspectType::ALL.iter().cloned().collect();
+        assert_eq!(distinct.len(), 6);
+        for aspect_type in AspectType::ALL.iter() {
+            assert_eq!(
+                AspectType::from_str(&aspect_type.to_string()),
+                Ok(*aspect_type)
+            );
+        }
+        assert!(AspectType::from_str("contents").is_err());
+
+        assert_eq!(test_content_aspect().aspect_type(), AspectType::Content);
+        assert_eq!(test_deletion_aspect().aspect_type(), AspectType::Deletion);
+        assert_eq!(test_link_add_aspect().type_hint(), "link_add");
+    }
+
+    #[test]
+    fn into_parts_test() {
+        let removal = (
+            LinkData::remove_from_link(
+                example_link_add().link(),
+                test_chain_header(),
+                test_agent_id(),
+            ),
+            vec![test_entry().address()],
+        );
+        let aspects = vec![
+            test_content_aspect(),
+            EntryAspect::Header(test_chain_header()),
+            test_link_add_aspect(),
+            EntryAspect::LinkRemove(removal, test_chain_header()),
+            test_update_aspect(),
+            test_deletion_aspect(),
+        ];
+        for aspect in aspects {
+            let (aspect_type, header, entry, link_data, removed) = aspect.clone().into_parts();
+            if aspect_type != AspectType::LinkRemove {
+                assert!(removed.is_empty());
+            }
+            let rebuilt = match (aspect_type, entry, link_data) {
+                (AspectType::Content, Some(entry), None) => EntryAspect::Content(entry, header),
+                (AspectType::Header, None, None) => EntryAspect::Header(header),
+                (AspectType::LinkAdd, None, Some(link_data)) => {
+                    EntryAspect::LinkAdd(link_data, header)
+                }
+                (AspectType::LinkRemove, None, Some(link_data)) => {
+                    EntryAspect::LinkRemove((link_data, removed), header)
+                }
+                (AspectType::Update, Some(entry), None) => EntryAspect::Update(entry, header),
+                (AspectType::Deletion, None, None) => EntryAspect::Deletion(header),
+                parts => panic!("unexpected parts {:?}", parts),
+            };
+            assert_eq!(rebuilt, aspect);
+        }
+    }
+
+    #[test]
+    fn author_matches_test() {
+        assert!(test_content_aspect().author_matches(&test_agent_id().address()));
+        assert!(!test_content_aspect().author_matches(&test_entry().address()));
+
+        let unsigned = ChainHeader::new(
+            &test_entry().entry_type(),
+            &test_entry().address(),
+            &Vec::new(),
+            &None,
+            &None,
+            &None,
+            &test_iso_8601(),
+        );
+        let anonymous = EntryAspect::Header(unsigned);
+        assert_eq!(anonymous.author(), None);
+        assert!(!anonymous.author_matches(&test_agent_id().address()));
+    }
+
+    #[test]
+    fn provenances_test() {
+        let signers = vec![
+            Provenance::new(test_agent_id().address(), Signature::from("author")),
+            Provenance::new(test_entry().address(), Signature::from("witness")),
+        ];
+        let header = ChainHeader::new(
+            &test_entry().entry_type(),
+            &test_entry().address(),
+            &signers,
+            &None,
+            &None,
+            &None,
+            &test_iso_8601(),
+        );
+        let aspect = EntryAspect::Content(test_entry(), header);
+        let sources: Vec<Address> = aspect
+            .provenances()
+            .iter()
+            .map(Provenance::source)
+            .collect();
+        assert_eq!(
+            sources,
+            vec![test_agent_id().address(), test_entry().address()]
+        );
+        assert_eq!(aspect.provenances(), &signers[..]);
+        assert_eq!(test_content_aspect().provenances().len(), 1);
+    }
+
+    #[test]
+    fn same_chain_test() {
+        assert!(test_content_aspect().same_chain(&test_link_add_aspect()));
+
+        let other_author = ChainHeader::new(
+            &test_entry().entry_type(),
+            &test_entry().address(),
+            &vec![Provenance::new(test_entry().address(), Signature::fake())],
+            &None,
+            &None,
+            &None,
+            &test_iso_8601(),
+        );
+        let other_chain = EntryAspect::Content(test_entry(), other_author);
+        assert!(!test_content_aspect().same_chain(&other_chain));
+        assert!(!other_chain.same_chain(&test_content_aspect()));
+
+        let unsigned = ChainHeader::new(
+            &test_entry().entry_type(),
+            &test_entry().address(),
+            &Vec::new(),
+            &None,
+            &None,
+            &None,
+            &test_iso_8601(),
+        );
+        let anonymous = EntryAspect::Header(unsigned);
+        assert!(!anonymous.same_chain(&anonymous));
+        assert!(!test_content_aspect().same_chain(&anonymous));
+    }
+
+    #[test]
+    fn as_ref_header_test() {
+        fn entry_address_of<T: AsRef<ChainHeader>>(item: T) -> Address {
+            item.as_ref().entry_address().clone()
+        }
+        assert_eq!(
+            entry_address_of(test_content_aspect()),
+            test_entry().address()
+        );
+        assert_eq!(
+            entry_address_of(&test_link_add_aspect()),
+            *test_link_add_aspect().header().entry_address()
+        );
+    }
+
+    #[test]
+    fn to_index_value_test() {
+        let aspect = test_content_aspect();
+        let value = aspect.to_index_value().unwrap();
+        assert_eq!(value["aspect_type"], json!("content"));
+        assert_eq!(value["address"], json!(aspect.address()));
+        assert_eq!(value["entry_address"], json!(test_entry().address()));
+        assert_eq!(value["author"], json!(test_agent_id().address()));
+        assert!(value.get("entry").is_none());
+        assert_eq!(aspect.to_index_value_with(false), Ok(value.clone()));
+
+        let with_payload = aspect.to_index_value_with(true).unwrap();
+        assert_eq!(
+            with_payload["entry"],
+            serde_json::to_value(test_entry()).unwrap()
+        );
+        assert_eq!(with_payload["address"], value["address"]);
+
+        let link_add = test_link_add_aspect().to_index_value_with(true).unwrap();
+        assert!(link_add.get("link_data").is_some());
+        assert!(link_add.get("entry").is_none());
+    }
+
+    #[test]
+    fn to_json_with_addresses_test() {
+        let aspect = test_deletion_aspect();
+        let value = aspect.to_json_with_addresses().unwrap();
+        assert_eq!(value["_addresses"]["address"], json!(aspect.address()));
+        assert_eq!(
+            value["_addresses"]["header_address"],
+            json!(aspect.header_address())
+        );
+        assert_eq!(
+            value["_addresses"]["entry_address"],
+            json!(aspect.entry_address().unwrap())
+        );
+        assert!(value.get("Deletion").is_some());
+        assert!(EntryAspect::try_from_content(&JsonString::from_json(&value.to_string())).is_err());
+    }
+
+    #[test]
+    fn link_accessors_test() {
+        let aspect = test_link_add_aspect();
+        assert_eq!(aspect.link_data(), Some(&example_link_add()));
+        assert_eq!(aspect.link_tag(), Some("foo-link-tag"));
+        assert_eq!(aspect.link_type(), Some("foo-link-type"));
+        let link = aspect.as_link().unwrap();
+        assert_eq!(link.base(), example_link_add().link().base());
+        assert_eq!(link.target(), example_link_add().link().target());
+
+        assert_eq!(test_content_aspect().link_data(), None);
+        assert_eq!(test_content_aspect().as_link(), None);
+        assert_eq!(test_content_aspect().link_tag(), None);
+        assert_eq!(test_content_aspect().link_type(), None);
+    }
+
+    #[test]
+    fn header_address_test() {
+        // pinned so a change in header serialization can't silently move it
+        let expected = Address::from("QmXkKtJ3ArSQwd5auBhdYfmen3gzwKwNJTdHF6dj71hi2R");
+        assert_eq!(test_chain_header().address(), expected);
+        assert_eq!(test_content_aspect().header_address(), expected);
+        assert_eq!(
+            EntryAspect::Header(test_chain_header()).header_address(),
+            expected
+        );
+    }
+
+    #[test]
+    fn carries_payload_test() {
+        let table = vec![
+            (test_content_aspect(), true),
+            (EntryAspect::Header(test_chain_header()), false),
+            (test_link_add_aspect(), true),
+            (
+                EntryAspect::LinkRemove(
+                    (example_link_add(), vec![test_entry().address()]),
+                    test_chain_header(),
+                ),
+                true,
+            ),
+            (test_update_aspect(), true),
+            (test_deletion_aspect(), false),
+        ];
+        for (aspect, carries_payload) in table {
+            assert_eq!(aspect.carries_payload(), carries_payload);
+            assert!(aspect.requires_source_for_validation());
+        }
+    }
+
+    #[test]
+    fn to_eav_test() {
+        let aspect = test_link_add_aspect();
+        let eav = aspect.to_eav().unwrap().unwrap();
+        assert_eq!(eav.entity(), example_link_add().link().base().clone());
+        assert_eq!(
+            eav.attribute(),
+            Attribute::LinkTag(String::from("foo-link-type"), String::from("foo-link-tag"))
+        );
+        assert_eq!(eav.value(), aspect.address());
+
+        assert_eq!(test_content_aspect().to_eav(), Ok(None));
+        assert!(EntryAspect::Deletion(test_chain_header()).to_eav().is_err());
+    }
+
+    #[test]
+    fn meta_attribute_test() {
+        assert_eq!(test_content_aspect().meta_attribute(), Ok(None));
+        assert_eq!(
+            test_link_add_aspect().meta_attribute(),
+            Ok(Some(String::from("link__foo-link-type__foo-link-tag")))
+        );
+        let link_remove = EntryAspect::LinkRemove(
+            (example_link_add(), vec![test_entry().address()]),
+            test_chain_header(),
+        );
+        assert_eq!(
+            link_remove.meta_attribute(),
+            Ok(Some(format!(
+                "removed_link__{}__foo-link-type__foo-link-tag",
+                test_entry().address()
+            )))
+        );
+        assert_eq!(
+            test_update_aspect().meta_attribute(),
+            Ok(Some(String::from("crud-link")))
+        );
+        assert_eq!(
+            test_deletion_aspect().meta_attribute(),
+            Ok(Some(String::from("crud-status")))
+        );
+        assert!(EntryAspect::Deletion(test_chain_header())
+            .meta_attribute()
+            .is_err());
+    }
+
+    #[test]
+    fn deleted_address_test() {
+        let fallback = test_entry_b().address();
+        let deletion = test_deletion_aspect();
+        assert_eq!(deletion.deleted_address(None), Ok(test_entry().address()));
+        assert_eq!(
+            deletion.deleted_address(Some(&fallback)),
+            Ok(test_entry().address())
+        );
+
+        let legacy = EntryAspect::Deletion(test_chain_header());
+        assert!(legacy.deleted_address(None).is_err());
+        assert_eq!(
+            legacy.deleted_address(Some(&fallback)),
+            Ok(fallback.clone())
+        );
+        assert!(test_content_aspect()
+            .deleted_address(Some(&fallback))
+            .is_err());
+    }
+
+    #[test]
+    fn address_mismatch_test() {
+        assert_eq!(test_content_aspect().address_mismatch(), None);
+        assert_eq!(test_update_aspect().address_mismatch(), None);
+        assert_eq!(test_link_add_aspect().address_mismatch(), None);
+
+        let corrupted = EntryAspect::Content(test_entry_b(), test_chain_header());
+        assert_eq!(
+            corrupted.address_mismatch(),
+            Some((test_entry().address(), test_entry_b().address()))
+        );
+    }
+
+    #[test]
+    fn is_self_link_test() {
+        let base = test_entry().address();
+        let self_link = LinkData::new_add(
+            &base,
+            &base,
+            "foo-link-tag",
+            "foo-link-type",
+            test_chain_header(),
+            test_agent_id(),
+        );
+        assert!(EntryAspect::LinkAdd(self_link, test_chain_header()).is_self_link());
+        assert!(!test_link_add_aspect().is_self_link());
+        assert!(!test_content_aspect().is_self_link());
+    }
+
+    #[test]
+    fn try_from_content_rejects_empty_link_remove_test() {
+        let link_remove = |removed: Vec<Address>| {
+            EntryAspect::LinkRemove(
+                (
+                    LinkData::remove_from_link(
+                        example_link_add().link(),
+                        test_chain_header(),
+                        test_agent_id(),
+                    ),
+                    removed,
+                ),
+                test_chain_header(),
+            )
+        };
+
+        let empty = link_remove(Vec::new());
+        assert!(EntryAspect::try_from_content(&empty.content()).is_err());
+        // the plain JSON decode still works for inspection
+        assert_eq!(EntryAspect::try_from(empty.content()).ok(), Some(empty));
+
+        let one = link_remove(vec![test_entry().address()]);
+        assert_eq!(
+            EntryAspect::try_from_content(&one.content()).ok(),
+            Some(one)
+        );
+    }
+
+    #[test]
+    fn has_crud_cycle_test() {
+        let entry = Entry::App(test_app_entry_type(), test_entry_value_b());
+        let header = ChainHeader::new(
+            &entry.entry_type(),
+            &entry.address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &Some(entry.address()),
+            &test_iso_8601(),
+        );
+        assert!(EntryAspect::Update(entry, header).has_crud_cycle());
+        assert!(!test_update_aspect().has_crud_cycle());
+        assert!(!test_deletion_aspect().has_crud_cycle());
+        assert!(!test_content_aspect().has_crud_cycle());
+    }
+
+    #[test]
+    fn same_target_test() {
+        let link = example_link_add().link().clone();
+        let add = EntryAspect::LinkAdd(
+            LinkData::add_from_link(&link, test_chain_header(), test_agent_id()),
+            test_chain_header(),
+        );
+        let remove = EntryAspect::LinkRemove(
+            (
+                LinkData::remove_from_link(&link, test_chain_header(), test_agent_id()),
+                vec![add.header().entry_address().clone()],
+            ),
+            test_chain_header(),
+        );
+        let other_tag = Link::new(link.base(), link.target(), link.link_type(), "other-tag");
+        let other_tag_add = EntryAspect::LinkAdd(
+            LinkData::add_from_link(&other_tag, test_chain_header(), test_agent_id()),
+            test_chain_header(),
+        );
+
+        assert!(add.same_target(&test_link_add_aspect()));
+        assert!(add.same_target(&remove));
+        assert!(!add.same_target(&other_tag_add));
+        assert!(!test_content_aspect().same_target(&test_content_aspect()));
+    }
+
+    #[test]
+    fn content_eq_test() {
+        let resigned =
+            EntryAspect::Content(test_entry(), test_chain_header_with_sig("other", None));
+        let later = EntryAspect::Content(
+            test_entry(),
+            test_header_for(&test_entry(), &Iso8601::from(1_600_000_000)),
+        );
+        assert_ne!(test_content_aspect(), resigned);
+        assert!(test_content_aspect().content_eq(&resigned));
+        assert!(test_content_aspect().content_eq(&later));
+        assert!(!test_content_aspect().content_eq(&EntryAspect::Header(test_chain_header())));
+        assert!(!test_content_aspect().content_eq(&test_update_aspect()));
+
+        // the same link, committed on top of another chain
+        let add_again = EntryAspect::LinkAdd(example_link_add(), test_chain_header());
+        assert!(add_again.content_eq(&test_link_add_aspect()));
+    }
+
+    #[test]
+    fn is_noop_against_test() {
+        let existing = vec![test_content_aspect(), test_link_add_aspect()];
+
+        // duplicate
+        assert!(test_content_aspect().is_noop_against(&existing));
+        // the same link added again by another header
+        let add_again = EntryAspect::LinkAdd(example_link_add(), test_chain_header());
+        assert!(add_again.is_noop_against(&existing));
+        // removing a link that never got added
+        let other_link = Link::new(
+            &test_entry().address(),
+            &test_entry_b().address(),
+            "foo-link-type",
+            "other-tag",
+        );
+        let remove_unknown = EntryAspect::LinkRemove(
+            (
+                LinkData::remove_from_link(&other_link, test_chain_header(), test_agent_id()),
+                vec![test_entry().address()],
+            ),
+            test_chain_header(),
+        );
+        assert!(remove_unknown.is_noop_against(&existing));
+
+        // new aspects
+        assert!(!test_update_aspect().is_noop_against(&existing));
+        assert!(!test_link_add_aspect().is_noop_against(&[]));
+    }
+
+    #[test]
+    fn merge_link_removals_test() {
+        let link_remove = |removed: Vec<Address>| {
+            let removal = (
+                LinkData::remove_from_link(
+                    example_link_add().link(),
+                    test_chain_header(),
+                    test_agent_id(),
+                ),
+                removed,
+            );
+            let header = test_header_for(&Entry::LinkRemove(removal.clone()), &test_iso_8601());
+            EntryAspect::LinkRemove(removal, header)
+        };
+        let (a, b) = (test_entry().address(), test_entry_b().address());
+        let mut sorted = vec![a.clone(), b.clone()];
+        sorted.sort();
+        let first = link_remove(vec![sorted[1].clone()]);
+
+        let merged = EntryAspect::merge_link_removals(vec![
+            test_content_aspect(),
+            first.clone(),
+            test_link_add_aspect(),
+            link_remove(vec![sorted[1].clone(), sorted[0].clone()]),
+        ])
+        .unwrap();
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0], test_content_aspect());
+        assert_eq!(merged[2], test_link_add_aspect());
+        match &merged[1] {
+            EntryAspect::LinkRemove((link_data, removed), header) => {
+                assert_eq!(*removed, sorted);
+                assert_eq!(link_data.link(), example_link_add().link());
+                assert_eq!(header, first.header());
+            }
+            other => panic!("expected a link_remove aspect, got {:?}", other),
+        }
+
+        // a single removal only gets canonicalized
+        assert_eq!(
+            EntryAspect::merge_link_removals(vec![first.clone()]),
+            Ok(vec![first])
+        );
+    }
+
+    #[test]
+    fn normalize_test() {
+        let link_remove = |removed: Vec<Address>| {
+            let removal = (
+                LinkData::remove_from_link(
+                    example_link_add().link(),
+                    test_chain_header(),
+                    test_agent_id(),
+                ),
+                removed,
+            );
+            let header = test_header_for(&Entry::LinkRemove(removal.clone()), &test_iso_8601());
+            EntryAspect::LinkRemove(removal, header)
+        };
+        let (a, b) = (test_entry().address(), test_entry_b().address());
+        let mut sorted = vec![a.clone(), b.clone()];
+        sorted.sort();
+        let unsorted = vec![sorted[1].clone(), sorted[0].clone(), sorted[1].clone()];
+
+        let normalized = link_remove(unsorted).normalize().unwrap();
+        match normalized {
+            EntryAspect::LinkRemove((_, removed), _) => assert_eq!(removed, sorted),
+            other => panic!("expected a link_remove aspect, got {:?}", other),
+        }
+        assert_eq!(test_content_aspect().normalize(), Ok(test_content_aspect()));
+
+        let reversed = link_remove(vec![sorted[1].clone(), sorted[0].clone()]);
+        let once = reversed.with_sorted_removals();
+        match &once {
+            EntryAspect::LinkRemove((_, removed), _) => assert_eq!(*removed, sorted),
+            other => panic!("expected a link_remove aspect, got {:?}", other),
+        }
+        assert_eq!(once.clone().with_sorted_removals(), once);
+        assert_eq!(
+            test_content_aspect().with_sorted_removals(),
+            test_content_aspect()
+        );
+        assert!(EntryAspect::Deletion(test_chain_header())
+            .normalize()
+            .is_err());
+    }
+
+    #[test]
+    fn is_expired_test() {
+        // test_content_aspect() is timestamped test_iso_8601()
+        let aspect = test_content_aspect();
+        let ttl = Duration::from_secs(3600);
+        let mut clock = FixedClock::new(test_iso_8601());
+
+        assert_eq!(aspect.age(&clock), Some(Duration::from_secs(0)));
+        assert!(!aspect.is_expired(ttl, &clock));
+
+        clock.advance(ttl).unwrap();
+        assert_eq!(aspect.age(&clock), Some(ttl));
+        assert!(!aspect.is_expired(ttl, &clock));
+
+        clock.advance(Duration::from_secs(1)).unwrap();
+        assert!(aspect.is_expired(ttl, &clock));
+
+        // timestamped in the future of now: can't be dated
+        clock.set((test_iso_8601() - ttl).unwrap());
+        assert_eq!(aspect.age(&clock), None);
+        assert!(!aspect.is_expired(Duration::from_secs(0), &clock));
+    }
+
+    #[test]
+    fn replay_key_test() {
+        let header = |author: &str, link: Option<Address>, timestamp: i64| {
+            EntryAspect::Header(ChainHeader::new(
+                &test_entry().entry_type(),
+                &test_entry().address(),
+                &vec![Provenance::new(Address::from(author), Signature::fake())],
+                &link,
+                &None,
+                &None,
+                &Iso8601::from(timestamp),
+            ))
+        };
+        let first = header("HcScAlice", None, 100);
+        let second = header("HcScAlice", Some(first.header_address()), 100);
+        let third = header("HcScAlice", Some(second.header_address()), 200);
+        let other = header("HcScBob", None, 150);
+
+        let mut aspects = vec![third.clone(), other.clone(), second.clone(), first.clone()];
+        aspects.sort_by_key(EntryAspect::replay_key);
+        assert_eq!(aspects, vec![first, second, other, third]);
+
+        // aspects sharing a header share the key
+        assert_eq!(
+            test_content_aspect().replay_key(),
+            EntryAspect::Header(test_chain_header()).replay_key()
+        );
+    }
+
+    #[test]
+    fn shard_key_test() {
+        // FNV-1a reference values
+        assert_eq!(fnv1a_64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let aspect = test_content_aspect();
+        let shard = aspect.shard_key(16).unwrap();
+        assert!(shard < 16);
+        assert_eq!(shard, aspect.shard_key(16).unwrap());
+        assert_eq!(shard, test_content_aspect().shard_key(16).unwrap());
+        // pinned so a change of algorithm can't go unnoticed
+        assert_eq!(shard, 5);
+        assert_eq!(aspect.shard_key(1000).unwrap(), 565);
+        assert!(aspect.shard_key(0).is_err());
+    }
+
+    #[test]
+    fn map_entry_test() {
+        let entry_b = Entry::App(test_app_entry_type(), test_entry_value_b());
+        let header_b = test_header_for(&entry_b, &test_iso_8601());
+
+        let stale = EntryAspect::Content(test_entry(), header_b.clone());
+        assert_eq!(
+            stale.map_entry(|_| Ok(Entry::App(test_app_entry_type(), test_entry_value_b()))),
+            Ok(EntryAspect::Content(entry_b, header_b))
+        );
+
+        assert!(test_content_aspect()
+            .map_entry(|_| Ok(test_entry_b()))
+            .is_err());
+        assert_eq!(
+            test_link_add_aspect().map_entry(|_| Ok(test_entry_b())),
+            Ok(test_link_add_aspect())
+        );
+    }
+
+    #[test]
+    fn to_pretty_json_test() {
+        let aspect = test_content_aspect();
+        let pretty = aspect.to_pretty_json().unwrap();
+
+        assert_ne!(pretty, String::from(aspect.content()));
+        assert_eq!(
+            EntryAspect::try_from(JsonString::from_json(&pretty)),
+            Ok(aspect)
+        );
+    }
+
+    #[test]
+    fn to_debug_map_test() {
+        let keys = |aspect: EntryAspect| -> Vec<String> {
+            aspect.to_debug_map().keys().cloned().collect()
+        };
+        let common = vec![
+            "aspect_type",
+            "author",
+            "entry_address",
+            "entry_type",
+            "header_address",
+        ];
+        assert_eq!(keys(test_content_aspect()), common);
+        assert_eq!(
+            keys(test_link_add_aspect()),
+            vec![
+                "aspect_type",
+                "author",
+                "entry_address",
+                "entry_type",
+                "header_address",
+                "link_base",
+                "link_tag",
+                "link_target",
+                "link_type",
+            ]
+        );
+        let with_crud_link = vec![
+            "aspect_type",
+            "author",
+            "crud_link",
+            "entry_address",
+            "entry_type",
+            "header_address",
+        ];
+        assert_eq!(keys(test_update_aspect()), with_crud_link);
+        assert_eq!(keys(test_deletion_aspect()), with_crud_link);
+
+        let map = test_link_add_aspect().to_debug_map();
+        assert_eq!(map["aspect_type"], "link_add");
+        assert_eq!(map["link_tag"], "foo-link-tag");
+    }
+
+    #[test]
+    fn short_address_test() {
+        assert_eq!(
+            short_address(&test_entry().address()),
+            String::from("Qma6Rfzv...Jcrq")
+        );
+        assert_eq!(
+            short_address(&Address::from(String::from("QmShort"))),
+            String::from("QmShort")
+        );
+        assert_eq!(
+            format!("{:?}", test_content_aspect()),
+            "EntryAspect::Content(Qma6Rfzv...Jcrq, Header[type: testEntryType, crud_link: None])",
+        );
+    }
+
+    #[test]
+    fn clone_into_store_test() {
+        let mut mirror = MemoryAspectStore::new();
+        let aspect = test_link_add_aspect();
+        let address = aspect.clone_into_store(&mut mirror).unwrap();
+        assert_eq!(address, aspect.address());
+        assert_eq!(mirror.get(&address), Ok(Some(aspect)));
+
+        let address = test_content_aspect().into_store(&mut mirror).unwrap();
+        assert_eq!(mirror.get(&address), Ok(Some(test_content_aspect())));
+        assert_eq!(mirror.len(), 2);
+    }
+
+    #[test]
+    fn content_type_consistent_test() {
+        assert!(test_content_aspect().content_type_consistent());
+        assert!(test_update_aspect().content_type_consistent());
+        assert!(test_link_add_aspect().content_type_consistent());
+
+        // right entry address, wrong entry type
+        let header = ChainHeader::new(
+            &EntryType::LinkAdd,
+            &test_entry().address(),
+            &test_provenances("sig"),
+            &None,
+            &None,
+            &None,
+            &test_iso_8601(),
+        );
+        let mismatched = EntryAspect::Content(test_entry(), header);
+        assert!(!mismatched.content_type_consistent());
+        assert!(mismatched.address_mismatch().is_none());
+    }
+
+    #[test]
+    fn canonical_bytes_test() {
+        let aspect = test_link_add_aspect();
+        let bytes = aspect.canonical_bytes().unwrap();
+        assert_eq!(
+            aspect.address(),
+            Address::encode_from_str(std::str::from_utf8(&bytes).unwrap(), HashAlgorithm::SHA2256)
+        );
+        assert_eq!(String::from(aspect.content()).into_bytes(), bytes);
+
+        // the same aspect, decoded instead of constructed
+        let decoded =
+            EntryAspect::try_from(serde_json::from_slice::<serde_json::Value>(&bytes).unwrap())
+                .unwrap();
+        assert_eq!(decoded.canonical_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn purge_payload_test() {
+        let content = test_content_aspect();
+        let purged = content.clone().purge_payload().unwrap();
+        assert_eq!(purged, EntryAspect::Header(content.header().clone()));
+        assert_eq!(purged.entry_address(), Ok(content.header().address()));
+
+        assert_eq!(
+            test_update_aspect().purge_payload(),
+            Ok(EntryAspect::Header(test_update_aspect().header().clone()))
+        );
+        assert_eq!(
+            test_deletion_aspect().purge_payload(),
+            Ok(test_deletion_aspect())
+        );
+        assert!(test_link_add_aspect().purge_payload().is_err());
+    }
+
+    #[test]
+    fn missing_crud_link_kind_test() {
+        let no_crud_link = EntryAspect::Deletion(test_chain_header());
+        assert_eq!(
+            no_crud_link.entry_address().unwrap_err().kind(),
+            ErrorKind::MissingCrudLink
+        );
+        assert_eq!(
+            no_crud_link.deleted_address(None).unwrap_err().kind(),
+            ErrorKind::MissingCrudLink
+        );
+        assert_eq!(
+            test_content_aspect()
+                .deleted_address(None)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn reserialize_stable_test() {
+        let mut removed = vec![test_entry().address(), test_entry_b().address()];
+        removed.sort();
+        let removal = (
+            LinkData::remove_from_link(
+                example_link_add().link(),
+                test_chain_header(),
+                test_agent_id(),
+            ),
+            removed,
+        );
+        let header = test_header_for(&Entry::LinkRemove(removal.clone()), &test_iso_8601());
+        let native = EntryAspect::LinkRemove(removal, header);
+
+        // e.g. written by an older version that stored aspects pretty-printed
+        let legacy_content = JsonString::from_json(&native.to_pretty_json().unwrap());
+        assert_ne!(
+            Address::encode_from_str(
+                &String::from(legacy_content.clone()),
+                HashAlgorithm::SHA2256
+            ),
+            native.address()
+        );
+        let legacy = EntryAspect::decode(&legacy_content, DecodeMode::TolerateUnknown)
+            .unwrap()
+            .unwrap();
+
+        let (content, address) = legacy.reserialize_stable().unwrap();
+        assert_eq!(address, native.address());
+        assert_eq!(content, native.content());
+        assert_eq!(native.reserialize_stable(), Ok((content, address)));
+    }
+
+    #[test]
+    fn with_link_tag_test() {
+        let original = test_link_add_aspect();
+        let retagged = original.clone().with_link_tag("new-tag").unwrap();
+        assert_eq!(retagged.link_tag(), Some("new-tag"));
+        assert_eq!(retagged.link_type(), original.link_type());
+        assert_eq!(retagged.entry_address(), original.entry_address());
+        assert_eq!(retagged.header(), original.header());
+        assert_ne!(retagged.address(), original.address());
+
+        assert!(test_content_aspect().with_link_tag("new-tag").is_err());
+    }
+
+    #[test]
+    fn map_link_test() {
+        let original = test_link_add_aspect();
+        let new_base = test_entry_b().address();
+        let rebased = original
+            .clone()
+            .map_link(|link| {
+                Ok(Link::new(
+                    &new_base,
+                    link.target(),
+                    link.link_type(),
+                    link.tag(),
+                ))
+            })
+            .unwrap();
+        assert_eq!(rebased.base_address(), Some(new_base));
+        assert_eq!(
+            rebased.as_link().unwrap().target(),
+            original.as_link().unwrap().target()
+        );
+        assert_eq!(rebased.header(), original.header());
+        assert_ne!(rebased.address(), original.address());
+
+        assert!(original
+            .map_link(|_| Err(HolochainError::ErrorGeneric(String::from("refused"))))
+            .is_err());
+        assert_eq!(
+            test_content_aspect().map_link(|_| panic!("not a link aspect")),
+            Ok(test_content_aspect())
+        );
+    }
+
+    #[test]
+    fn fingerprint_test() {
+        let fingerprint = test_link_add_aspect().fingerprint().unwrap();
+        assert_eq!(test_link_add_aspect().fingerprint(), Ok(fingerprint));
+        let decoded = EntryAspect::try_from_content(&test_link_add_aspect().content()).unwrap();
+        assert_eq!(decoded.fingerprint(), Ok(fingerprint));
+        assert_ne!(test_content_aspect().fingerprint(), Ok(fingerprint));
+    }
+
+    #[test]
+    fn describe_test() {
+        assert_eq!(
+            test_content_aspect().describe(),
+            "content: testEntryType entry Qma6Rfzv...Jcrq"
+        );
+        assert!(test_link_add_aspect().describe().starts_with("link_add: "));
+        assert!(test_link_add_aspect()
+            .describe()
+            .ends_with("[type: foo-link-type, tag: foo-link-tag]"));
+        assert_eq!(
+            test_deletion_aspect().describe(),
+            format!(
+                "deletion: {} deleted",
+                short_address(&test_entry().address())
+            )
+        );
+    }
+
+    #[test]
+    fn describe_eav_test() {
+        let mut store = MemoryAspectStore::new();
+        let link_add = test_link_add_aspect();
+        store.put(link_add.clone()).unwrap();
+        let row = link_add.to_eav().unwrap().unwrap();
+        assert_eq!(describe_eav(&row, &store), Ok(link_add.describe()));
+
+        // a row whose value is not known, and one that does not record its value
+        assert!(describe_eav(&row, &MemoryAspectStore::new()).is_err());
+        store.put(test_content_aspect()).unwrap();
+        let unrelated = eav::new(
+            &row.entity(),
+            &row.attribute(),
+            &test_content_aspect().address(),
+        )
+        .unwrap();
+        assert!(describe_eav(&unrelated, &store).is_err());
+    }
+
+    #[test]
+    fn base_address_test() {
+        let link_base = example_link_add().link().base().clone();
+        assert_eq!(
+            test_link_add_aspect().base_address(),
+            Some(link_base.clone())
+        );
+        let removal = LinkData::remove_from_link(
+            example_link_add().link(),
+            test_chain_header(),
+            test_agent_id(),
+        );
+        let link_remove =
+            EntryAspect::LinkRemove((removal, vec![test_entry().address()]), test_chain_header());
+        assert_eq!(link_remove.base_address(), Some(link_base));
+
+        assert_eq!(
+            test_update_aspect().base_address(),
+            Some(test_entry().address())
+        );
+        assert_eq!(
+            test_deletion_aspect().base_address(),
+            Some(test_entry().address())
+        );
+        assert_eq!(
+            EntryAspect::Deletion(test_chain_header()).base_address(),
+            None
+        );
+
+        assert_eq!(test_content_aspect().base_address(), None);
+        assert_eq!(
+            EntryAspect::Header(test_chain_header()).base_address(),
+            None
+        );
+        for aspect in vec![
+            test_link_add_aspect(),
+            test_update_aspect(),
+            test_deletion_aspect(),
+        ] {
+            assert_eq!(aspect.base_address(), aspect.entry_address().ok());
+        }
+    }
+
+    #[test]
+    fn serialized_size_test() {
+        let aspect = test_link_add_aspect();
+        assert_eq!(
+            aspect.serialized_size(),
+            Ok(String::from(aspect.content()).len())
+        );
+        assert!(test_deletion_aspect().gossip_priority() > test_content_aspect().gossip_priority());
+    }
+
+    #[test]
+    fn approx_payload_bytes_test() {
+        let aspect = test_content_aspect();
+        let entry_size = serde_json::to_vec(&test_entry()).unwrap().len();
+        assert_eq!(aspect.approx_payload_bytes(), entry_size);
+        assert!(aspect.approx_payload_bytes() < aspect.serialized_size().unwrap());
+        assert!(test_link_add_aspect().approx_payload_bytes() > 0);
+        assert_eq!(test_deletion_aspect().approx_payload_bytes(), 0);
+    }
+
+    #[test]
+    fn previous_header_address_test() {
+        let previous = test_chain_header();
+        let header = ChainHeader::new(
+            &test_entry_b().entry_type(),
+            &test_entry_b().address(),
+            &test_provenances("sig"),
+            &Some(previous.address()),
+            &None,
+            &None,
+            &test_iso_8601(),
+        );
+        let aspect = EntryAspect::Content(test_entry_b(), header);
+        assert_eq!(aspect.previous_header_address(), Some(previous.address()));
+
+        assert_eq!(test_content_aspect().previous_header_address(), None);
+    }
+
+    #[test]
+    fn try_from_str_test() {
+        let json = String::from(test_link_add_aspect().content());
+        assert_eq!(
+            EntryAspect::try_from(json.as_str()),
+            Ok(test_link_add_aspect())
+        );
+        assert!(EntryAspect::try_from("{\"LinkAdd\": ").is_err());
+        assert!(EntryAspect::try_from("\"not an aspect\"").is_err());
+    }
+
+    #[test]
+    fn checked_constructors_test() {
+        assert_eq!(
+            EntryAspect::checked_content(test_entry(), test_chain_header()),
+            Ok(test_content_aspect())
+        );
+        let update = test_update_aspect();
+        assert!(EntryAspect::checked_update(test_entry(), update.header().clone()).is_err());
+        match update.clone() {
+            EntryAspect::Update(entry, header) => {
+                assert_eq!(EntryAspect::checked_update(entry, header), Ok(update))
+            }
+            other => panic!("expected an update aspect, got {:?}", other),
+        }
+        assert_eq!(
+            EntryAspect::checked_deletion(test_deletion_aspect().header().clone()),
+            Ok(test_deletion_aspect())
+        );
+        // no CRUD link
+        assert!(EntryAspect::checked_deletion(test_chain_header()).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! LinkRemoveAspectBuilder collects the addresses a link removal tombstones
+//! and builds the `LinkRemove` entry and aspect from them.
+//! Callers that know how many links they remove can preallocate with
+//! with_capacity() and add them in bulk with extend_removed().
+
+use crate::{
+    chain_header::ChainHeader, entry::Entry, link::link_data::LinkData,
+    network::entry_aspect::EntryAspect,
+};
+use holochain_persistence_api::cas::content::Address;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkRemoveAspectBuilder {
+    link_data: LinkData,
+    removed: Vec<Address>,
+}
+
+impl LinkRemoveAspectBuilder {
+    pub fn new(link_data: LinkData) -> Self {
+        Self::with_capacity(link_data, 0)
+    }
+
+    /// Reserves room for `capacity` removed addresses up front.
+    pub fn with_capacity(link_data: LinkData, capacity: usize) -> Self {
+        LinkRemoveAspectBuilder {
+            link_data,
+            removed: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Number of removed addresses that fit without reallocating
+    pub fn capacity(&self) -> usize {
+        self.removed.capacity()
+    }
+
+    pub fn removed(&self) -> &[Address] {
+        &self.removed
+    }
+
+    /// Adds a single address of a `LinkAdd` entry that gets removed.
+    pub fn add_removed(mut self, address: Address) -> Self {
+        self.removed.push(address);
+        self
+    }
+
+    /// Adds the addresses of all `LinkAdd` entries yielded by `removed`.
+    pub fn extend_removed<I: IntoIterator<Item = Address>>(mut self, removed: I) -> Self {
+        self.removed.extend(removed);
+        self
+    }
+
+    /// The `LinkRemove` entry, e.g. to build its header from.
+    pub fn entry(&self) -> Entry {
+        Entry::LinkRemove((self.link_data.clone(), self.removed.clone()))
+    }
+
+    /// The `LinkRemove` aspect, with `header` being the header of entry().
+    pub fn build(self, header: ChainHeader) -> EntryAspect {
+        EntryAspect::LinkRemove((self.link_data, self.removed), header)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id, chain_header::test_chain_header, link::example_link,
+        network::entry_aspect::tests::test_header_for, time::test_iso_8601,
+    };
+    use holochain_persistence_api::cas::content::AddressableContent;
+
+    fn test_remove_data() -> LinkData {
+        LinkData::remove_from_link(&example_link(), test_chain_header(), test_agent_id())
+    }
+
+    #[test]
+    fn extend_removed_test() {
+        let removed: Vec<Address> = (0..1000)
+            .map(|i| Address::from(format!("QmRemovedLink{}", i)))
+            .collect();
+
+        let builder = LinkRemoveAspectBuilder::with_capacity(test_remove_data(), removed.len());
+        let capacity = builder.capacity();
+        assert!(capacity >= 1000);
+
+        let builder = builder.extend_removed(removed.iter().cloned());
+        // everything fit into the preallocated buffer
+        assert_eq!(builder.capacity(), capacity);
+        assert_eq!(builder.removed(), &removed[..]);
+
+        let header = test_header_for(&builder.entry(), &test_iso_8601());
+        let aspect = builder.build(header.clone());
+        assert_eq!(
+            aspect,
+            EntryAspect::LinkRemove((test_remove_data(), removed), header.clone())
+        );
+        assert_eq!(aspect.entry_address().unwrap(), *header.entry_address());
+    }
+
+    #[test]
+    fn add_removed_test() {
+        let address = example_link().target().clone();
+        let builder = LinkRemoveAspectBuilder::new(test_remove_data()).add_removed(address.clone());
+        assert_eq!(
+            builder.entry(),
+            Entry::LinkRemove((test_remove_data(), vec![address]))
+        );
+        assert_eq!(
+            builder.entry().address(),
+            LinkRemoveAspectBuilder::new(test_remove_data())
+                .extend_removed(vec![example_link().target().clone()])
+                .entry()
+                .address()
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! MetaAspect is the subset of EntryAspects that are meta items, i.e. every
+//! variant but `Content`, for code that only handles meta data and should
+//! not have to deal with a `Content` aspect it can never get.
+//! The variants are the same as those of EntryAspect, see there for their meaning.
+
+use crate::{
+    chain_header::ChainHeader, entry::Entry, error::HolochainError, link::link_data::LinkData,
+    network::entry_aspect::EntryAspect,
+};
+use holochain_persistence_api::cas::content::Address;
+use std::convert::TryFrom;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MetaAspect {
+    Header(ChainHeader),
+    LinkAdd(LinkData, ChainHeader),
+    LinkRemove((LinkData, Vec<Address>), ChainHeader),
+    Update(Entry, ChainHeader),
+    Deletion(ChainHeader),
+}
+
+impl MetaAspect {
+    pub fn header(&self) -> &ChainHeader {
+        match self {
+            MetaAspect::Header(header)
+            | MetaAspect::LinkAdd(_, header)
+            | MetaAspect::LinkRemove(_, header)
+            | MetaAspect::Update(_, header)
+            | MetaAspect::Deletion(header) => header,
+        }
+    }
+}
+
+/// Errors for `Content` aspects, which are not meta aspects.
+impl TryFrom<EntryAspect> for MetaAspect {
+    type Error = HolochainError;
+    fn try_from(aspect: EntryAspect) -> Result<Self, Self::Error> {
+        Ok(match aspect {
+            EntryAspect::Header(header) => MetaAspect::Header(header),
+            EntryAspect::LinkAdd(link_data, header) => MetaAspect::LinkAdd(link_data, header),
+            EntryAspect::LinkRemove(removal, header) => MetaAspect::LinkRemove(removal, header),
+            EntryAspect::Update(entry, header) => MetaAspect::Update(entry, header),
+            EntryAspect::Deletion(header) => MetaAspect::Deletion(header),
+            EntryAspect::Content(_, header) => {
+                return Err(HolochainError::ErrorGeneric(format!(
+                    "Content aspect of entry {} is not a meta aspect",
+                    header.entry_address()
+                )))
+            }
+        })
+    }
+}
+
+impl From<MetaAspect> for EntryAspect {
+    fn from(meta: MetaAspect) -> Self {
+        match meta {
+            MetaAspect::Header(header) => EntryAspect::Header(header),
+            MetaAspect::LinkAdd(link_data, header) => EntryAspect::LinkAdd(link_data, header),
+            MetaAspect::LinkRemove(removal, header) => EntryAspect::LinkRemove(removal, header),
+            MetaAspect::Update(entry, header) => EntryAspect::Update(entry, header),
+            MetaAspect::Deletion(header) => EntryAspect::Deletion(header),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        agent::test_agent_id,
+        chain_header::test_chain_header,
+        entry::test_entry,
+        link::link_data::example_link_add,
+        network::{
+            entry_aspect::tests::{
+                test_content_aspect, test_deletion_aspect, test_header_for, test_link_add_aspect,
+                test_update_aspect,
+            },
+            link_remove_builder::LinkRemoveAspectBuilder,
+        },
+        time::test_iso_8601,
+    };
+    use holochain_persistence_api::cas::content::AddressableContent;
+
+    #[test]
+    fn round_trip_test() {
+        let link_remove = LinkRemoveAspectBuilder::new(LinkData::remove_from_link(
+            example_link_add().link(),
+            test_chain_header(),
+            test_agent_id(),
+        ))
+        .add_removed(test_entry().address());
+        let header = test_header_for(&link_remove.entry(), &test_iso_8601());
+        for aspect in vec![
+            EntryAspect::Header(test_chain_header()),
+            test_link_add_aspect(),
+            link_remove.build(header),
+            test_update_aspect(),
+            test_deletion_aspect(),
+        ] {
+            let meta = MetaAspect::try_from(aspect.clone()).unwrap();
+            assert_eq!(meta.header(), aspect.header());
+            assert_eq!(EntryAspect::from(meta), aspect);
+        }
+    }
+
+    #[test]
+    fn content_is_not_meta_test() {
+        assert!(MetaAspect::try_from(test_content_aspect()).is_err());
+    }
+}
@@ -1,2 +1,22 @@
+pub mod aspect_batch;
+#[cfg(feature = "compression")]
+pub mod aspect_compression;
+pub mod aspect_counter;
+pub mod aspect_filter;
+#[cfg(any(test, feature = "test_support"))]
+pub mod aspect_fixtures;
+pub mod aspect_list;
+pub mod aspect_ndjson;
+pub mod aspect_store;
+pub mod aspect_validation;
+pub mod by_time;
+pub mod cached_aspect;
+pub mod compact_aspect;
+pub mod content_aspect_builder;
+pub mod duplicate_guard;
 pub mod entry_aspect;
+pub mod link_remove_builder;
+pub mod meta_aspect;
+pub mod origin_tagged_aspect;
 pub mod query;
+pub mod received_aspect;
@@ -0,0 +1,78 @@
+//! OriginTaggedAspect records how an aspect came to exist locally (see
+//! AspectOrigin), so that trust decisions can take it into account.
+//! Like with ReceivedAspect, the origin is not part of the aspect: address,
+//! equality and hashing are those of the inner aspect.
+
+use crate::network::entry_aspect::EntryAspect;
+use std::{
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AspectOrigin {
+    /// Created from an entry committed to our own chain
+    Authored,
+    /// Received from a peer
+    Relayed,
+    /// Rebuilt locally from stored entries and headers
+    Reconstructed,
+}
+
+#[derive(Clone, Debug)]
+pub struct OriginTaggedAspect {
+    pub aspect: EntryAspect,
+    pub origin: AspectOrigin,
+}
+
+impl OriginTaggedAspect {
+    pub fn new(aspect: EntryAspect, origin: AspectOrigin) -> Self {
+        OriginTaggedAspect { aspect, origin }
+    }
+
+    pub fn into_aspect(self) -> EntryAspect {
+        self.aspect
+    }
+}
+
+impl Deref for OriginTaggedAspect {
+    type Target = EntryAspect;
+
+    fn deref(&self) -> &EntryAspect {
+        &self.aspect
+    }
+}
+
+impl PartialEq for OriginTaggedAspect {
+    fn eq(&self, other: &OriginTaggedAspect) -> bool {
+        self.aspect == other.aspect
+    }
+}
+
+impl Eq for OriginTaggedAspect {}
+
+impl Hash for OriginTaggedAspect {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.aspect.hash(state)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::network::entry_aspect::tests::{test_content_aspect, test_link_add_aspect};
+    use holochain_persistence_api::cas::content::AddressableContent;
+
+    #[test]
+    fn origin_does_not_change_identity_test() {
+        let authored = OriginTaggedAspect::new(test_content_aspect(), AspectOrigin::Authored);
+        let relayed = OriginTaggedAspect::new(test_content_aspect(), AspectOrigin::Relayed);
+        assert_eq!(authored.address(), test_content_aspect().address());
+        assert_eq!(authored.address(), relayed.address());
+        assert_eq!(authored, relayed);
+        assert_ne!(
+            authored,
+            OriginTaggedAspect::new(test_link_add_aspect(), AspectOrigin::Authored)
+        );
+    }
+}
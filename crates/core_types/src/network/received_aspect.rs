@@ -0,0 +1,85 @@
+//! ReceivedAspect carries routing metadata (which peer sent an aspect and when)
+//! along with the aspect, e.g. for debugging gossip loops.
+//! The metadata is not part of the aspect: address, equality and hashing
+//! are those of the inner aspect.
+
+use crate::{network::entry_aspect::EntryAspect, time::Iso8601};
+use std::{
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+#[derive(Clone, Debug)]
+pub struct ReceivedAspect {
+    pub aspect: EntryAspect,
+    /// Id of the peer the aspect was received from
+    pub from: Option<String>,
+    pub received_at: Option<Iso8601>,
+}
+
+impl ReceivedAspect {
+    pub fn new(aspect: EntryAspect, from: Option<String>, received_at: Option<Iso8601>) -> Self {
+        ReceivedAspect {
+            aspect,
+            from,
+            received_at,
+        }
+    }
+
+    pub fn into_aspect(self) -> EntryAspect {
+        self.aspect
+    }
+}
+
+impl From<EntryAspect> for ReceivedAspect {
+    fn from(aspect: EntryAspect) -> Self {
+        ReceivedAspect::new(aspect, None, None)
+    }
+}
+
+impl Deref for ReceivedAspect {
+    type Target = EntryAspect;
+
+    fn deref(&self) -> &EntryAspect {
+        &self.aspect
+    }
+}
+
+impl PartialEq for ReceivedAspect {
+    fn eq(&self, other: &ReceivedAspect) -> bool {
+        self.aspect == other.aspect
+    }
+}
+
+impl Eq for ReceivedAspect {}
+
+impl Hash for ReceivedAspect {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.aspect.hash(state)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        network::entry_aspect::tests::{test_content_aspect, test_link_add_aspect},
+        time::test_iso_8601,
+    };
+    use holochain_persistence_api::cas::content::AddressableContent;
+
+    #[test]
+    fn received_aspect_delegates_test() {
+        let received = ReceivedAspect::new(
+            test_content_aspect(),
+            Some(String::from("HcScPeer")),
+            Some(test_iso_8601()),
+        );
+        assert_eq!(received.address(), received.aspect.address());
+        assert_eq!(received.type_hint(), "content");
+
+        // metadata is ignored for equality
+        assert_eq!(received, ReceivedAspect::from(test_content_aspect()));
+        assert_ne!(received, ReceivedAspect::from(test_link_add_aspect()));
+    }
+}
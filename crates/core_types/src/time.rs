@@ -12,7 +12,7 @@ use std::{
     fmt,
     ops::{Add, Sub},
     str::FromStr,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 /// Represents a timeout for an HDK function. The usize interface defaults to ms.  Also convertible
@@ -542,6 +542,53 @@ impl<D: Into<Duration>> Sub<D> for &Iso8601 {
  * }
  */
 
+/// Source of the current time for code that needs "now", e.g. to tell the age of an aspect.
+/// Taking a Clock instead of reading the system time directly keeps such code deterministic
+/// and testable: use SystemClock in production and FixedClock in tests.
+pub trait Clock {
+    fn now(&self) -> Iso8601;
+}
+
+/// The system's wall clock.  Per the note above, this is not available on the WASM target, and
+/// must not be used from within Zome functions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Iso8601 {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        Iso8601::new(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+    }
+}
+
+/// A clock that stands still at a given time until it is set or advanced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(Iso8601);
+
+impl FixedClock {
+    pub fn new(now: Iso8601) -> Self {
+        FixedClock(now)
+    }
+
+    pub fn set(&mut self, now: Iso8601) {
+        self.0 = now;
+    }
+
+    /// Moves the clock forward by `duration`, failing (and leaving the clock as is) on overflow.
+    pub fn advance<D: Into<Duration>>(&mut self, duration: D) -> Result<(), HolochainError> {
+        self.0 = (self.0 + duration)?;
+        Ok(())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> Iso8601 {
+        self.0
+    }
+}
+
 /// Serialization w/ serde_json to/from String.  This means that a timestamp will be deserialized to
 /// an Iso8601 and validated, which may fail, returning a serde::de::Error.  Upon serialization, the
 /// canonicalized ISO 8601 / RFC 3339 version of the timestamp will be used.
@@ -1262,4 +1309,16 @@ pub mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_fixed_clock() {
+        let mut clock = FixedClock::new(test_iso_8601());
+        assert_eq!(clock.now(), test_iso_8601());
+        clock.advance(Duration::from_secs(60)).unwrap();
+        assert_eq!(clock.now(), Iso8601::from(1_539_228_278));
+        clock.set(Iso8601::from(0));
+        assert_eq!(clock.now(), Iso8601::from(0));
+
+        assert!(SystemClock.now() > test_iso_8601());
+    }
 }